@@ -1,12 +1,28 @@
 use std::{fs, io};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use arg::Args;
 use indexmap::IndexSet;
+use normalize_path::NormalizePath;
 
 use papyri_lang::{compiler, errors, utils};
 
 fn main() {
-    let mut args: ProgramArgs = arg::parse_args();
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if argv.first().map(String::as_str) == Some("test") {
+        let mut args: TestArgs = parse_args_from(&argv[1..]);
+        if args.paths.is_empty() {
+            args.paths.push(".".to_string());
+        }
+        
+        if let Err(msg) = TestMain::new(args).run() {
+            eprintln!("{msg}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    
+    let mut args: ProgramArgs = parse_args_from(&argv);
     
     if args.print_version {
         let version = env!("CARGO_PKG_VERSION");
@@ -24,9 +40,25 @@ fn main() {
     }
 }
 
+/// Parses command-line arguments. This is equivalent to `arg::parse_args`, but
+/// allows a subcommand to be skipped first.
+fn parse_args_from<T: Args>(argv: &[String]) -> T {
+    match T::from_args(argv.iter().map(String::as_str)) {
+        Ok(args) => args,
+        Err(arg::ParseError::HelpRequested(help)) => {
+            println!("{help}");
+            std::process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        },
+    }
+}
+
 #[derive(Args)]
 ///papyri
-///Compiles Papyri to HTML.
+///Compiles Papyri to HTML. Use `papyri test` to run Papyri test files.
 struct ProgramArgs {
     #[arg(long = "version")]
     ///Print version number and then exit
@@ -48,23 +80,89 @@ struct ProgramArgs {
     ///Compile to text instead of HTML
     text: bool,
     
+    #[arg(long)]
+    ///Check the HTML output against HTML5 content models
+    validate: bool,
+    
+    #[arg(long)]
+    ///Check the HTML output for common accessibility problems
+    a11y: bool,
+    
     #[arg(short, long = "out")]
     ///Output directory (default is the current directory)
     out_dir: Option<std::path::PathBuf>,
     
+    #[arg(long = "snapshot")]
+    ///Compare output with the snapshots in this directory, instead of writing
+    ///output files
+    snapshot_dir: Option<std::path::PathBuf>,
+    
+    #[arg(long = "snapshot-update")]
+    ///Rewrite changed snapshots and delete stale ones; requires --snapshot
+    snapshot_update: bool,
+    
+    #[arg(long = "max-call-depth")]
+    ///Maximum depth of nested function calls (default 5000)
+    max_call_depth: Option<usize>,
+    
+    #[arg(long = "fetch-cache")]
+    ///Cache responses from '@fetch' functions in this directory
+    fetch_cache_dir: Option<std::path::PathBuf>,
+    
+    #[arg(long = "fetch-ttl")]
+    ///Seconds before a cached response is revalidated (default 86400)
+    fetch_ttl: Option<u64>,
+    
+    #[arg(long = "fetch-timeout")]
+    ///Timeout for '@fetch' requests, in seconds (default 30)
+    fetch_timeout: Option<u64>,
+    
+    #[arg(long)]
+    ///Serve '@fetch' responses only from the cache; requires --fetch-cache
+    offline: bool,
+    
     ///The Papyri source file(s) to compile. If none are specified, the current
     ///directory is searched for Papyri source files.
     paths: Vec<String>,
 }
 
+#[derive(Args)]
+///papyri test
+///Runs the test cases declared in Papyri test files.
+struct TestArgs {
+    #[arg(short, long)]
+    ///Only report failing test cases
+    silent: bool,
+    
+    ///The Papyri test file(s) to run. If none are specified, the current
+    ///directory is searched for Papyri test files (ending in `.test.papyri`).
+    paths: Vec<String>,
+}
+
 struct Main {
     options: ProgramArgs,
     ctx: compiler::Context,
+    link_checker: compiler::LinkChecker,
+    cross_refs: compiler::CrossReferences,
+    
+    /// Output files with references to labels which may be on other pages;
+    /// these are written once all of the source files have been compiled.
+    pending_files: Vec<(PathBuf, compiler::OutFile)>,
+    
+    /// The source files which were skipped because they were unchanged, and
+    /// their output paths. These are compiled again if other pages may refer
+    /// to labels on them.
+    skipped_files: Vec<(PathBuf, PathBuf)>,
+    
+    /// The snapshot files which have been compared with output files.
+    snapshots_compared: HashSet<PathBuf>,
+    num_snapshots_changed: u32,
 }
 
 enum SourceFileResult {
     OkWroteFiles(u32),
     SkippedLibrary,
+    SkippedTest,
     SkippedUnchanged,
     SkippedNoOutput,
     Failed,
@@ -78,11 +176,39 @@ impl Main {
             errors::ReportingLevel::Warning
         };
         
-        let ctx = compiler::Context::new(reporting_level, options.out_dir.as_deref());
-        Main {options, ctx}
+        let mut ctx = compiler::Context::new(reporting_level, options.out_dir.as_deref());
+        let fetch_options = &mut ctx.fetch_options;
+        fetch_options.cache_dir = options.fetch_cache_dir.clone();
+        fetch_options.offline = options.offline;
+        if let Some(ttl) = options.fetch_ttl {
+            fetch_options.cache_ttl = std::time::Duration::from_secs(ttl);
+        }
+        if let Some(timeout) = options.fetch_timeout {
+            fetch_options.timeout = Some(std::time::Duration::from_secs(timeout));
+        }
+        if let Some(max_call_depth) = options.max_call_depth {
+            ctx.limits.max_call_depth = max_call_depth;
+        }
+        let link_checker = compiler::LinkChecker::new(options.out_dir.as_deref());
+        Main {
+            options,
+            ctx,
+            link_checker,
+            cross_refs: compiler::CrossReferences::new(),
+            pending_files: Vec::new(),
+            skipped_files: Vec::new(),
+            snapshots_compared: HashSet::new(),
+            num_snapshots_changed: 0,
+        }
     }
     
     fn run(&mut self) -> Result<(), String> {
+        if self.options.snapshot_update && self.options.snapshot_dir.is_none() {
+            return Err("--snapshot-update requires --snapshot".to_string());
+        } else if self.options.offline && self.options.fetch_cache_dir.is_none() {
+            return Err("--offline requires --fetch-cache".to_string());
+        }
+        
         let in_dir = std::env::current_dir()
             .and_then(fs::canonicalize)
             .map_err(|e| format!("File error: {e} in current working directory"))?;
@@ -92,13 +218,14 @@ impl Main {
         let mut num_skipped = 0;
         let mut num_files_written = 0;
         
-        for src_path in self.get_source_paths()? {
+        for src_path in get_source_paths(&self.options.paths)? {
             match self.process_source_file(&src_path, &in_dir)? {
                 SourceFileResult::OkWroteFiles(k) => {
                     num_ok += 1;
                     num_files_written += k;
                 },
                 SourceFileResult::SkippedLibrary |
+                SourceFileResult::SkippedTest |
                 SourceFileResult::SkippedUnchanged |
                 SourceFileResult::SkippedNoOutput => {
                     num_skipped += 1;
@@ -109,11 +236,38 @@ impl Main {
             }
         }
         
-        let msg = format!(
-            "{num_files_written} file{} written; {num_ok} OK, {num_failed} failed, {num_skipped} skipped",
+        if !self.pending_files.is_empty() {
+            self.add_skipped_labels()?;
+        }
+        self.ctx.reset();
+        for (out_path, file) in std::mem::take(&mut self.pending_files) {
+            let file = match file {
+                compiler::OutFile::HTML(html, mode) => compiler::OutFile::HTML(self.cross_refs.resolve(&out_path, &html, &mut self.ctx), mode),
+                file => file,
+            };
+            self.emit_out_file(&out_path, in_dir.as_path(), file)?;
+        }
+        if let Some(snapshot_dir) = self.options.snapshot_dir.clone() {
+            self.check_stale_snapshots(&snapshot_dir)?;
+        }
+        self.link_checker.check(&mut self.ctx);
+        self.ctx.diagnostics.print_to_stderr();
+        
+        let mut msg = format!(
+            "{num_files_written} file{} {}; {num_ok} OK, {num_failed} failed, {num_skipped} skipped",
             utils::text::pluralise(num_files_written),
+            if self.options.snapshot_dir.is_some() { "compared" } else { "written" },
         );
-        if num_failed > 0 {
+        let num_changed = self.num_snapshots_changed;
+        if self.options.snapshot_dir.is_some() {
+            msg += &format!(
+                "; {num_changed} snapshot{} {}",
+                utils::text::pluralise(num_changed),
+                if self.options.snapshot_update { "updated" } else { "changed" },
+            );
+        }
+        
+        if num_failed > 0 || (num_changed > 0 && !self.options.snapshot_update) {
             Err(msg)
         } else {
             if !self.options.silent { println!("{msg}"); }
@@ -121,46 +275,6 @@ impl Main {
         }
     }
     
-    fn get_source_paths(&self) -> Result<IndexSet<PathBuf, fxhash::FxBuildHasher>, String> {
-        let mut source_paths = IndexSet::default();
-        for path_str in self.options.paths.iter() {
-            if utils::text::looks_like_glob(path_str) {
-                // glob pattern
-                let paths = glob::glob(path_str)
-                    .map_err(|e| format!("Pattern error: {e} at \"{path_str}\""))?;
-                
-                for entry in paths {
-                    let path = entry
-                        .map_err(|e| format!("File error: {e} at \"{path_str}\""))?;
-                    if utils::sourcefile::is_papyri_file(&path) {
-                        source_paths.insert(path);
-                    }
-                }
-            } else {
-                let path = PathBuf::from(path_str);
-                if path.is_dir() {
-                    // directory
-                    let paths = utils::relpath::find_papyri_source_files_in_dir(
-                        &path,
-                        |p, e| eprintln!("File error: {e} in \"{}\"", p.to_string_lossy()),
-                    );
-                    if let Ok(paths) = paths {
-                        for p in paths {
-                            source_paths.insert(path.join(p));
-                        }
-                    }
-                } else if utils::sourcefile::is_papyri_file(&path) {
-                    // source file
-                    source_paths.insert(path);
-                } else {
-                    eprintln!("File \"{path_str}\" is not a Papyri source file");
-                }
-            }
-        }
-        source_paths.sort();
-        Ok(source_paths)
-    }
-    
     fn process_source_file(&mut self, src_path: &Path, in_dir: &Path) -> Result<SourceFileResult, String> {
         let src_path_str = src_path.to_string_lossy();
         
@@ -169,27 +283,30 @@ impl Main {
                 println!("{src_path_str} (library, skipping)");
             }
             return Ok(SourceFileResult::SkippedLibrary);
+        } else if utils::sourcefile::is_papyri_test(src_path) {
+            if !self.options.silent {
+                println!("{src_path_str} (test, skipping)");
+            }
+            return Ok(SourceFileResult::SkippedTest);
         }
         
         let out_path = self.get_out_path(src_path, in_dir)?;
         
-        if self.options.skip_unchanged && is_unchanged(src_path, &out_path) {
+        if self.options.skip_unchanged && self.options.snapshot_dir.is_none() && is_unchanged(src_path, &out_path) {
             if !self.options.silent {
                 println!("{src_path_str} (unchanged, skipping)");
             }
+            self.skipped_files.push((src_path.to_path_buf(), out_path));
             return Ok(SourceFileResult::SkippedUnchanged);
         }
         
-        self.ctx.reset();
-        let result = self.ctx
-            .load_uncached(src_path)
-            .map_err(|e| format!("Error loading \"{src_path_str}\": {e}"))?;
-        
-        let mut to_write = self.ctx.out_files
-            .as_mut()
-            .map_or_else(Vec::new, |o| o.take_iter().collect());
-        if !result.out.is_empty() {
-            to_write.push((out_path, result.out));
+        let to_write = self.compile_source_file(src_path, &out_path)?;
+        if self.options.validate || self.options.a11y {
+            for (_, file) in to_write.iter() {
+                let Some(html) = self.html_page(file) else { continue; };
+                if self.options.validate { self.ctx.validate_html(html); }
+                if self.options.a11y { self.ctx.lint_accessibility(html); }
+            }
         }
         
         let diagnostics = &mut self.ctx.diagnostics;
@@ -207,13 +324,79 @@ impl Main {
             Ok(SourceFileResult::SkippedNoOutput)
         } else {
             let k = to_write.len() as u32;
-            for (out_path, html) in to_write.into_iter() {
-                self.write_out_file(&out_path, html)?;
+            for (out_path, file) in to_write.iter() {
+                if let compiler::OutFile::HTML(html, _) = file {
+                    self.cross_refs.add_page(out_path, html);
+                }
+            }
+            for (out_path, file) in to_write.into_iter() {
+                if matches!(&file, compiler::OutFile::HTML(html, _) if compiler::CrossReferences::has_unresolved(html)) {
+                    self.pending_files.push((out_path, file));
+                } else {
+                    self.emit_out_file(&out_path, in_dir, file)?;
+                }
             }
             Ok(SourceFileResult::OkWroteFiles(k))
         }
     }
     
+    /// Compiles a source file, and returns its output files. These include the
+    /// page itself, if it has any content.
+    fn compile_source_file(&mut self, src_path: &Path, out_path: &Path) -> Result<Vec<(PathBuf, compiler::OutFile)>, String> {
+        self.ctx.reset();
+        self.ctx.page_path = self.options.out_dir.as_ref()
+            .and_then(|out_dir| out_path.strip_prefix(out_dir).ok())
+            .map(Path::to_path_buf);
+        let result = self.ctx
+            .load_uncached(src_path)
+            .map_err(|e| format!("Error loading \"{}\": {e}", src_path.to_string_lossy()))?;
+        
+        let mut out_files = self.ctx.out_files
+            .as_mut()
+            .map_or_else(Vec::new, |o| o.take_iter().collect());
+        if !result.out.is_empty() {
+            out_files.push((out_path.to_path_buf(), compiler::OutFile::HTML(result.out, None)));
+        }
+        Ok(out_files)
+    }
+    
+    /// Compiles the source files which were skipped because they were
+    /// unchanged, and adds the labels on their pages to the cross-references.
+    /// Their output files are not written again, and diagnostics are not
+    /// reported.
+    fn add_skipped_labels(&mut self) -> Result<(), String> {
+        for (src_path, out_path) in std::mem::take(&mut self.skipped_files) {
+            for (out_path, file) in self.compile_source_file(&src_path, &out_path)? {
+                if let compiler::OutFile::HTML(html, _) = file {
+                    self.cross_refs.add_page(&out_path, &html);
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Writes an output file, or compares it with its snapshot, after adding it
+    /// to the link checker.
+    fn emit_out_file(&mut self, out_path: &Path, in_dir: &Path, file: compiler::OutFile) -> Result<(), String> {
+        if let Some(html) = self.html_page(&file) {
+            self.link_checker.add_page(out_path, html);
+        }
+        if let Some(snapshot_dir) = self.options.snapshot_dir.clone() {
+            self.compare_snapshot(&snapshot_dir, out_path, in_dir, file)
+        } else {
+            self.write_out_file(out_path, file)
+        }
+    }
+    
+    /// Returns the content of the given output file, if it is rendered as an
+    /// HTML page.
+    fn html_page<'f>(&self, file: &'f compiler::OutFile) -> Option<&'f compiler::HTML> {
+        match file {
+            compiler::OutFile::HTML(html, mode) if mode.map_or(!self.options.text, |m| m == compiler::RenderMode::HTML) => Some(html),
+            _ => None,
+        }
+    }
+    
     fn get_out_path(&self, src_path: &Path, in_dir: &Path) -> Result<PathBuf, String> {
         let mut out_path = if let Some(ref out_dir) = self.options.out_dir {
             if let Some(p) = utils::relpath::make_relative(in_dir, src_path) {
@@ -233,18 +416,84 @@ impl Main {
         }
     }
     
-    fn write_out_file(&mut self, path: &Path, html: compiler::HTML) -> Result<(), String> {
-        let path_str = path.to_string_lossy();
+    fn compare_snapshot(&mut self, snapshot_dir: &Path, out_path: &Path, in_dir: &Path, file: compiler::OutFile) -> Result<(), String> {
+        let rel_path = self.options.out_dir.as_ref()
+            .and_then(|out_dir| out_path.strip_prefix(out_dir).ok())
+            .map(Path::to_path_buf)
+            .or_else(|| utils::relpath::make_relative(in_dir, out_path))
+            .ok_or_else(|| format!("No sensible snapshot path for \"{}\"", out_path.to_string_lossy()))?;
+        let snapshot_path = snapshot_dir.join(rel_path).normalize();
+        let snapshot_path_str = snapshot_path.to_string_lossy();
+        self.snapshots_compared.insert(snapshot_path.clone());
         
-        path.parent()
-            .map_or(Ok(()), fs::create_dir_all)
-            .map_err(|e| format!("Failed to create directory \"{path_str}\": {e}"))?;
+        let mut out = Vec::new();
+        self.ctx.render_out_file(&file, !self.options.text, &mut out)
+            .map_err(|e| format!("Failed to render \"{}\": {e}", out_path.to_string_lossy()))?;
+        let out = String::from_utf8_lossy(&out);
         
-        let mut out_writer = fs::File::create(path)
-            .map(io::BufWriter::new)
-            .map_err(|e| format!("Failed to create file \"{path_str}\": {e}"))?;
+        let old = match fs::read_to_string(&snapshot_path) {
+            Ok(old) => Some(old),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("Failed to read snapshot \"{snapshot_path_str}\": {e}")),
+        };
+        if old.as_deref() == Some(out.as_ref()) {
+            return Ok(());
+        }
+        self.num_snapshots_changed += 1;
         
-        self.ctx.render(&html, !self.options.text, &mut out_writer)
+        if self.options.snapshot_update {
+            snapshot_path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&snapshot_path, out.as_bytes()))
+                .map_err(|e| format!("Failed to write snapshot \"{snapshot_path_str}\": {e}"))?;
+            if !self.options.silent {
+                println!("    => {snapshot_path_str} (updated)");
+            }
+        } else {
+            let (old, new) = if self.options.text {
+                (old.unwrap_or_default(), out.into_owned())
+            } else {
+                (utils::diff::split_html_lines(&old.unwrap_or_default()), utils::diff::split_html_lines(&out))
+            };
+            println!("--- {snapshot_path_str}");
+            println!("+++ {}", out_path.to_string_lossy());
+            print!("{}", utils::diff::unified_diff(&old, &new, 3));
+        }
+        Ok(())
+    }
+    
+    /// Reports snapshot files which were not compared with any output file,
+    /// or deletes them if `--snapshot-update` is used.
+    fn check_stale_snapshots(&mut self, snapshot_dir: &Path) -> Result<(), String> {
+        if !snapshot_dir.exists() {
+            return Ok(());
+        }
+        for entry in walkdir::WalkDir::new(snapshot_dir) {
+            let entry = entry.map_err(|e| format!("Failed to read snapshots: {e}"))?;
+            let snapshot_path = entry.path().normalize();
+            if !entry.file_type().is_file() || self.snapshots_compared.contains(&snapshot_path) {
+                continue;
+            }
+            self.num_snapshots_changed += 1;
+            
+            let snapshot_path_str = snapshot_path.to_string_lossy();
+            if self.options.snapshot_update {
+                fs::remove_file(&snapshot_path)
+                    .map_err(|e| format!("Failed to delete snapshot \"{snapshot_path_str}\": {e}"))?;
+                if !self.options.silent {
+                    println!("{snapshot_path_str} (stale snapshot, deleted)");
+                }
+            } else {
+                println!("{snapshot_path_str} (stale snapshot, no matching output)");
+            }
+        }
+        Ok(())
+    }
+    
+    fn write_out_file(&mut self, path: &Path, file: compiler::OutFile) -> Result<(), String> {
+        let path_str = path.to_string_lossy();
+        
+        self.ctx.write_out_file(path, &file, !self.options.text)
             .map_err(|e| format!("Failed to write file \"{path_str}\": {e}"))?;
         
         if !self.options.silent {
@@ -254,6 +503,150 @@ impl Main {
     }
 }
 
+struct TestMain {
+    options: TestArgs,
+    ctx: compiler::Context,
+}
+
+impl TestMain {
+    fn new(options: TestArgs) -> TestMain {
+        let ctx = compiler::Context::new(errors::ReportingLevel::Warning, None);
+        TestMain {options, ctx}
+    }
+    
+    fn run(&mut self) -> Result<(), String> {
+        let mut num_passed = 0;
+        let mut num_failed = 0;
+        let mut num_files = 0;
+        
+        for src_path in get_source_paths(&self.options.paths)? {
+            if !utils::sourcefile::is_papyri_test(&src_path) { continue; }
+            num_files += 1;
+            
+            let src_path_str = src_path.to_string_lossy();
+            self.ctx.reset();
+            self.ctx.tests = Some(Vec::new());
+            let result = self.ctx.load_uncached(&src_path);
+            let outcomes = self.ctx.tests.take().unwrap_or_default();
+            if let Err(e) = result {
+                eprintln!("Error loading \"{src_path_str}\": {e}");
+                num_failed += 1;
+                continue;
+            }
+            
+            let diagnostics = &self.ctx.diagnostics;
+            diagnostics.print_to_stderr();
+            if diagnostics.num_errors > 0 {
+                // errors in the test file itself are a failure
+                eprintln!("{src_path_str} ({})", diagnostics.summary());
+                num_failed += 1;
+            }
+            
+            // in silent mode, the path is only printed before the first failure
+            let mut printed_path = !self.options.silent;
+            if printed_path {
+                println!("{src_path_str}");
+            }
+            for outcome in outcomes {
+                if outcome.passed {
+                    num_passed += 1;
+                    if !self.options.silent {
+                        println!("    PASS {}", outcome.name);
+                    }
+                } else {
+                    num_failed += 1;
+                    if !printed_path {
+                        println!("{src_path_str}");
+                        printed_path = true;
+                    }
+                    println!("    FAIL {}\n    {}", outcome.name, outcome.location);
+                    print_indented(&describe_failure(&outcome));
+                }
+            }
+        }
+        
+        let msg = format!(
+            "{num_files} test file{}; {num_passed} passed, {num_failed} failed",
+            utils::text::pluralise(num_files),
+        );
+        if num_failed > 0 {
+            Err(msg)
+        } else {
+            if !self.options.silent { println!("{msg}"); }
+            Ok(())
+        }
+    }
+}
+
+fn describe_failure(outcome: &compiler::TestOutcome) -> String {
+    use compiler::TestExpectation;
+    use utils::diff::{split_html_lines, unified_diff};
+    
+    match (&outcome.expected, &outcome.actual) {
+        (TestExpectation::Output(expected), Ok(actual)) => {
+            let diff = unified_diff(&split_html_lines(expected), &split_html_lines(actual), 3);
+            format!("output differs from expected:\n--- expected\n+++ actual\n{diff}")
+        },
+        (TestExpectation::Output(_), Err(diagnostics)) => {
+            format!("expected output, but diagnostics were reported:\n{diagnostics}")
+        },
+        (TestExpectation::Error(kind), Ok(actual)) => {
+            format!("expected {kind}, but compiled successfully:\n{actual}\n")
+        },
+        (TestExpectation::Error(kind), Err(diagnostics)) => {
+            format!("expected {kind}, but diagnostics were:\n{diagnostics}")
+        },
+    }
+}
+
+fn print_indented(s: &str) {
+    for line in s.lines() {
+        println!("        {line}");
+    }
+}
+
+fn get_source_paths(path_strs: &[String]) -> Result<IndexSet<PathBuf, fxhash::FxBuildHasher>, String> {
+    let mut source_paths = IndexSet::default();
+    for path_str in path_strs.iter() {
+        if utils::text::looks_like_glob(path_str) {
+            // glob pattern
+            let paths = glob::glob(path_str)
+                .map_err(|e| format!("Pattern error: {e} at \"{path_str}\""))?;
+            
+            for entry in paths {
+                let path = entry
+                    .map_err(|e| format!("File error: {e} at \"{path_str}\""))?;
+                if utils::sourcefile::is_papyri_file(&path) {
+                    source_paths.insert(path);
+                }
+            }
+        } else {
+            let path = PathBuf::from(path_str);
+            if path.is_dir() {
+                // directory
+                let paths = utils::relpath::find_papyri_source_files_in_dir(
+                    &utils::filesystem::OsFileSystem,
+                    &path,
+                    |p, e| eprintln!("File error: {e} in \"{}\"", p.to_string_lossy()),
+                );
+                if let Ok(paths) = paths {
+                    for p in paths {
+                        source_paths.insert(path.join(p));
+                    }
+                }
+            } else if utils::sourcefile::is_papyri_file(&path) {
+                // source file
+                source_paths.insert(path);
+            } else {
+                eprintln!("File \"{path_str}\" is not a Papyri source file");
+            }
+        }
+    }
+    source_paths.sort();
+    Ok(source_paths)
+}
+
+
 fn is_unchanged(src_path: &Path, out_path: &Path) -> bool {
     let modified_time = |p: &Path| fs::metadata(p).and_then(|m| m.modified());
     
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use crate::errors;
+use crate::utils::{str_ids, taginfo, StringPool};
+use crate::utils::sourcefile::SourceRange;
+use super::context::Context;
+use super::html::HTML;
+use super::tag::Tag;
+
+/// Indicates whether the given HTML has any text which a screen reader would
+/// read, including the `alt` text of images.
+fn has_text(html: &HTML) -> bool {
+    match html {
+        HTML::Tag(tag) if tag.name_id == str_ids::IMG => {
+            matches!(tag.get_attr(str_ids::ALT), Some(Some(alt)) if !alt.trim().is_empty())
+        },
+        HTML::Tag(tag) => has_text(&tag.content),
+        HTML::Sequence(seq) => seq.iter().any(has_text),
+        HTML::Text(t) => !t.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// Checks HTML for common accessibility problems, collecting a warning for
+/// each. Each warning is attributed to the source range of the innermost tag
+/// which was written in Papyri source.
+struct Linter<'a> {
+    string_pool: &'a StringPool,
+    
+    /// The IDs referenced by `for` attributes of `<label>` tags.
+    label_targets: HashSet<String>,
+    
+    /// The level of the most recent heading.
+    heading_level: Option<u8>,
+    warnings: Vec<(errors::Warning, SourceRange)>,
+}
+
+impl <'a> Linter<'a> {
+    fn report(&mut self, e: errors::Warning, range: Option<SourceRange>) {
+        if let Some(range) = range {
+            self.warnings.push((e, range));
+        }
+    }
+    
+    /// Returns the value of the named attribute, where the attribute name is
+    /// written with `-` rather than `_`.
+    fn attr<'t>(&self, tag: &'t Tag, name: &str) -> Option<Option<&'t str>> {
+        tag.attributes.iter()
+            .find(|&(&k, _)| self.string_pool.get(k).replace('_', "-") == name)
+            .map(|(_, v)| v.as_deref())
+    }
+    
+    /// Indicates whether the tag has an accessible name given by an attribute.
+    fn has_aria_label(&self, tag: &Tag) -> bool {
+        ["aria-label", "aria-labelledby", "title"].iter()
+            .any(|name| matches!(self.attr(tag, name), Some(Some(v)) if !v.trim().is_empty()))
+    }
+    
+    fn collect_label_targets(&mut self, html: &HTML) {
+        match html {
+            HTML::Tag(tag) => {
+                if tag.name_id == str_ids::LABEL {
+                    if let Some(Some(id)) = self.attr(tag, "for") {
+                        self.label_targets.insert(id.to_string());
+                    }
+                }
+                self.collect_label_targets(&tag.content);
+            },
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.collect_label_targets(child);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    fn visit(&mut self, html: &HTML, in_label: bool, range: Option<SourceRange>) {
+        match html {
+            HTML::Tag(tag) => self.visit_tag(tag, in_label, range),
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.visit(child, in_label, range);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    fn visit_tag(&mut self, tag: &Tag, in_label: bool, range: Option<SourceRange>) {
+        let range = tag.range.or(range);
+        match tag.name_id {
+            str_ids::HTML => {
+                if !matches!(tag.get_attr(str_ids::LANG), Some(Some(lang)) if !lang.is_empty()) {
+                    self.report(errors::Warning::MissingLang, range);
+                }
+            },
+            str_ids::IMG => {
+                if tag.get_attr(str_ids::ALT).is_none() && !self.has_aria_label(tag) {
+                    self.report(errors::Warning::MissingAltText, range);
+                }
+            },
+            str_ids::A => {
+                if tag.get_attr(str_ids::HREF).is_some() && !has_text(&tag.content) && !self.has_aria_label(tag) {
+                    self.report(errors::Warning::EmptyLinkText, range);
+                }
+            },
+            str_ids::TABLE => {
+                if !tag.content.contains_tag(str_ids::TH) {
+                    self.report(errors::Warning::TableWithoutHeaders, range);
+                }
+            },
+            str_ids::INPUT | str_ids::SELECT | str_ids::TEXTAREA => {
+                let is_labelled_type = tag.name_id != str_ids::INPUT || !matches!(
+                    tag.get_attr(str_ids::TYPE),
+                    Some(Some("button" | "hidden" | "image" | "reset" | "submit")),
+                );
+                let has_label = in_label
+                    || self.has_aria_label(tag)
+                    || matches!(tag.get_attr(str_ids::ID), Some(Some(id)) if self.label_targets.contains(id));
+                if is_labelled_type && !has_label {
+                    let name = self.string_pool.get(tag.name_id);
+                    self.report(errors::Warning::MissingFormLabel(name), range);
+                }
+            },
+            name_id => if let Some(level) = taginfo::heading_level(name_id) {
+                if let Some(previous) = self.heading_level.filter(|&previous| level > previous + 1) {
+                    self.report(errors::Warning::HeadingLevelSkipped(previous, level), range);
+                }
+                self.heading_level = Some(level);
+            },
+        }
+        
+        let in_label = in_label || tag.name_id == str_ids::LABEL;
+        self.visit(&tag.content, in_label, range);
+    }
+}
+
+impl Context {
+    /// Checks the given HTML for common accessibility problems, and reports a
+    /// warning for each one found: images without `alt` text, skipped heading
+    /// levels, links without text, tables without header cells, form inputs
+    /// without labels, and `<html>` tags without a `lang` attribute. Problems
+    /// in HTML which was not written in Papyri source, such as fetched HTML,
+    /// are only reported if it is inside a tag which was.
+    pub fn lint_accessibility(&mut self, html: &HTML) {
+        let mut linter = Linter {
+            string_pool: &self.string_pool,
+            label_targets: HashSet::new(),
+            heading_level: None,
+            warnings: Vec::new(),
+        };
+        linter.collect_label_targets(html);
+        linter.visit(html, false, None);
+        
+        for (e, range) in linter.warnings {
+            let src = self.source_files.get(range.src_id);
+            self.diagnostics.report_static(e, src, range);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::hash::Hasher;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::errors;
+use crate::utils::sourcefile::SourceRange;
+use super::base::Compiler;
+use super::render::OutFile;
+use super::value::RcStr;
+
+/// Inserts a hash of the given contents into the file name of the given path,
+/// before the file extension if there is one. For example, `img/logo.png`
+/// becomes `img/logo.0123456789abcdef.png`.
+fn fingerprinted_path(path: &str, contents: &[u8]) -> String {
+    let mut hasher = fxhash::FxHasher64::default();
+    hasher.write(contents);
+    let hash = format!("{:016x}", hasher.finish());
+    
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    match path[name_start..].rfind('.') {
+        Some(i) if i > 0 => {
+            let (stem, ext) = path.split_at(name_start + i);
+            format!("{stem}.{hash}{ext}")
+        },
+        _ => format!("{path}.{hash}"),
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Copies the asset at the given path, relative to the directory of the
+    /// source file where the call occurs, to the same path relative to the
+    /// directory of the page being compiled. If `hash` is true, the file name
+    /// is fingerprinted with a hash of the file's contents. Returns the path
+    /// of the copied file, relative to the page, for use as a URL.
+    pub(super) fn copy_asset(&mut self, path_str: RcStr, hash: bool, call_range: SourceRange) -> errors::PapyriResult<RcStr> {
+        let path = self.resolve_relative_path(call_range.src_id, path_str.as_ref(), false)?;
+        let contents = self.ctx.file_system.read(&path)
+            .map_err(|e| errors::RuntimeError::FileReadError(path_str.clone(), e))?;
+        
+        let url: RcStr = if hash {
+            fingerprinted_path(path_str.as_ref(), &contents).into()
+        } else {
+            path_str
+        };
+        let page_dir = self.ctx.page_path.as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""));
+        let out_path = page_dir.join(url.as_ref());
+        self.ctx.push_out_file(out_path.to_string_lossy().into(), OutFile::Bytes(Rc::from(contents)))?;
+        Ok(url)
+    }
+}
@@ -1,7 +1,8 @@
+use crate::errors;
 use super::context::Context;
 use super::frame::ActiveFrame;
 use super::html::HTML;
-use super::value::Dict;
+use super::value::{Dict, Value};
 
 /// The result of compiling a Papyri source file. The output may be incomplete
 /// if there were errors during compilation. 
@@ -15,6 +16,20 @@ pub struct CompileResult {
     pub exports: Dict,
 }
 
+/// The result of compiling a Papyri source string with
+/// `Context::compile_document`.
+pub struct Document {
+    /// The HTML output. This may be incomplete if there were errors during
+    /// compilation.
+    pub html: HTML,
+    
+    /// The values exported by the Papyri source, as a dictionary.
+    pub exports: Value,
+    
+    /// The errors and warnings reported during compilation.
+    pub diagnostics: errors::Diagnostics,
+}
+
 pub(super) struct Compiler<'a> {
     pub(super) ctx: &'a mut Context,
     pub(super) call_stack: Vec<ActiveFrame>,
@@ -0,0 +1,779 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::errors;
+use crate::utils::str_ids;
+use super::base::Compiler;
+use super::context::Context;
+use super::html::HTML;
+use super::tag::Tag;
+use super::value::RcStr;
+
+/// The maximum length of an element ID generated for a bibliography entry.
+const MAX_ID_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The style in which citations and bibliography entries are rendered.
+pub(super) enum CitationStyle {
+    /// Citations show the authors' family names and the year, such as
+    /// "(Smith and Jones 2020)". Bibliography entries are sorted by author.
+    AuthorYear,
+    
+    /// Citations show numbers, such as "[1]". Entries are numbered, and the
+    /// bibliography is sorted, in the order they are first cited.
+    Numeric,
+}
+
+impl CitationStyle {
+    /// Returns the citation style with the given name, which is either
+    /// `author-year` or `numeric`.
+    pub(super) fn from_name(name: &str) -> Option<CitationStyle> {
+        match name {
+            "author-year" => Some(CitationStyle::AuthorYear),
+            "numeric" => Some(CitationStyle::Numeric),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// A person's name, as the author of a bibliography entry.
+struct Name {
+    family: String,
+    given: Option<String>,
+}
+
+impl Name {
+    /// Parses a name from BibTeX, which is either "Family, Given" or "Given
+    /// Family". A name in braces, such as an organisation, is not split.
+    fn parse_bibtex(s: &str) -> Name {
+        let s = s.trim();
+        if s.starts_with('{') && s.ends_with('}') && !s[1..s.len() - 1].contains(['{', '}']) {
+            return Name {family: clean_latex(s), given: None};
+        }
+        let (family, given) = if let Some((family, given)) = s.split_once(',') {
+            (family, Some(given))
+        } else if let Some((given, family)) = s.rsplit_once(char::is_whitespace) {
+            (family, Some(given))
+        } else {
+            (s, None)
+        };
+        Name {
+            family: clean_latex(family),
+            given: given.map(clean_latex).filter(|g| !g.is_empty()),
+        }
+    }
+    
+    /// Returns this name in the form "Family, G. N.", for a bibliography
+    /// entry.
+    fn with_initials(&self) -> String {
+        let Some(given) = &self.given else {
+            return self.family.clone();
+        };
+        let initials = given.split([' ', '-', '.'])
+            .filter_map(|part| part.chars().next())
+            .map(|c| format!("{c}."))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{}, {initials}", self.family)
+    }
+}
+
+#[derive(Debug, Default)]
+/// An entry in a bibliography, loaded from a BibTeX or CSL-JSON file.
+pub(super) struct BibEntry {
+    authors: Vec<Name>,
+    year: Option<String>,
+    title: Option<String>,
+    container: Option<String>,
+    volume: Option<String>,
+    issue: Option<String>,
+    pages: Option<String>,
+    publisher: Option<String>,
+    url: Option<String>,
+    doi: Option<String>,
+}
+
+impl BibEntry {
+    /// Returns the text of an author-year citation of this entry, such as
+    /// "Smith and Jones 2020" or "Smith et al. 2020".
+    fn author_year(&self) -> String {
+        let authors = match self.authors.as_slice() {
+            [] => self.title.clone().unwrap_or_default(),
+            [a] => a.family.clone(),
+            [a, b] => format!("{} and {}", a.family, b.family),
+            [a, ..] => format!("{} et al.", a.family),
+        };
+        let year = self.year.as_deref().unwrap_or("n.d.");
+        format!("{authors} {year}")
+    }
+    
+    /// The key which entries are sorted by in an author-year bibliography.
+    fn sort_key(&self) -> (String, &str, &str) {
+        let author = self.authors.first()
+            .map_or_else(String::new, |a| a.family.to_lowercase());
+        (author, self.year.as_deref().unwrap_or(""), self.title.as_deref().unwrap_or(""))
+    }
+    
+    /// Renders this entry for a bibliography, in the form "Authors (Year).
+    /// Title. *Container*, Volume(Issue), Pages. Publisher. URL".
+    fn to_html(&self) -> HTML {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        
+        if !self.authors.is_empty() {
+            let names: Vec<_> = self.authors.iter()
+                .map(Name::with_initials)
+                .collect();
+            text += &match names.split_last() {
+                Some((last, [])) => last.clone(),
+                Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+                None => String::new(),
+            };
+            text.push(' ');
+        }
+        text += &format!("({}). ", self.year.as_deref().unwrap_or("n.d."));
+        if let Some(title) = &self.title {
+            text += title.trim_end_matches('.');
+            text += ". ";
+        }
+        
+        if let Some(container) = &self.container {
+            parts.push(HTML::text(std::mem::take(&mut text)));
+            parts.push(HTML::tag(str_ids::I, HTML::text(container.as_str())));
+            if let Some(volume) = &self.volume {
+                text += &format!(", {volume}");
+                if let Some(issue) = &self.issue {
+                    text += &format!("({issue})");
+                }
+            }
+            if let Some(pages) = &self.pages {
+                text += &format!(", {pages}");
+            }
+            text += ". ";
+        }
+        if let Some(publisher) = &self.publisher {
+            text += &format!("{publisher}. ");
+        }
+        
+        let url = self.doi.as_ref()
+            .map(|doi| format!("https://doi.org/{doi}"))
+            .or_else(|| self.url.clone());
+        if let Some(url) = url {
+            parts.push(HTML::text(text));
+            let link = Tag::new(str_ids::A, HTML::text(url.as_str()))
+                .str_attr(str_ids::HREF, &url);
+            parts.push(link.into());
+        } else {
+            parts.push(HTML::text(text.trim_end()));
+        }
+        parts.into_iter().collect()
+    }
+}
+
+/// The bibliography entries which have been loaded, by their keys.
+pub(super) type Bibliography = HashMap<RcStr, Rc<BibEntry>>;
+
+type LatexChars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// Returns the combining character for a LaTeX accent command, such as `\"`
+/// or `\c`.
+fn latex_accent(command: &str) -> Option<char> {
+    let accent = match command {
+        "\"" => '\u{308}',
+        "'" => '\u{301}',
+        "`" => '\u{300}',
+        "^" => '\u{302}',
+        "~" => '\u{303}',
+        "=" => '\u{304}',
+        "." => '\u{307}',
+        "H" => '\u{30b}',
+        "c" => '\u{327}',
+        "k" => '\u{328}',
+        "v" => '\u{30c}',
+        "u" => '\u{306}',
+        "r" => '\u{30a}',
+        _ => return None,
+    };
+    Some(accent)
+}
+
+/// Returns the letter for a LaTeX command which stands for one, such as
+/// `\ss` for "\u{df}".
+fn latex_letter(command: &str) -> Option<char> {
+    let letter = match command {
+        "ss" => '\u{df}',
+        "o" => '\u{f8}',
+        "O" => '\u{d8}',
+        "aa" => '\u{e5}',
+        "AA" => '\u{c5}',
+        "ae" => '\u{e6}',
+        "AE" => '\u{c6}',
+        "oe" => '\u{153}',
+        "OE" => '\u{152}',
+        "l" => '\u{142}',
+        "L" => '\u{141}',
+        "i" => '\u{131}',
+        "j" => '\u{237}',
+        _ => return None,
+    };
+    Some(letter)
+}
+
+/// Reads the name of a LaTeX command after its backslash; this is either a
+/// sequence of letters, or a single other character. As in LaTeX, spaces
+/// after a command made of letters are skipped.
+fn latex_command(chars: &mut LatexChars) -> String {
+    let mut command = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+        command.push(c);
+    }
+    if command.is_empty() {
+        command.extend(chars.next());
+    } else {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+    command
+}
+
+/// Reads the letter which a LaTeX accent command applies to. The letter may
+/// be in braces, and may be a command such as `\i`; a dotless i or j becomes
+/// an ordinary letter, since the accent takes the place of its dot.
+fn latex_accent_base(chars: &mut LatexChars) -> Option<char> {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    let braced = chars.next_if_eq(&'{').is_some();
+    let base = match chars.next()? {
+        '}' if braced => return None,
+        '\\' => latex_letter(&latex_command(chars)),
+        c => Some(c),
+    };
+    if braced {
+        chars.next_if_eq(&'}');
+    }
+    base.map(|c| match c {
+        '\u{131}' => 'i',
+        '\u{237}' => 'j',
+        c => c,
+    })
+}
+
+/// Converts a BibTeX field value to plain text. Braces are removed, accent
+/// commands and letter commands such as `\"o` and `\ss` are converted to
+/// Unicode characters, and whitespace is collapsed. Other commands, such as
+/// `\emph`, are removed, leaving their arguments.
+fn clean_latex(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => {},
+            '\\' => {
+                let command = latex_command(&mut chars);
+                if let Some(accent) = latex_accent(&command) {
+                    if let Some(base) = latex_accent_base(&mut chars) {
+                        out.push(base);
+                        out.push(accent);
+                    }
+                } else if let Some(letter) = latex_letter(&command) {
+                    out.push(letter);
+                } else if !command.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    // an escaped character, such as `\&`
+                    out += &command;
+                }
+            },
+            '~' => out.push(' '),
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                if chars.peek() == Some(&'-') {
+                    chars.next();
+                    out.push('\u{2014}');
+                } else {
+                    out.push('\u{2013}');
+                }
+            },
+            c => out.push(c),
+        }
+    }
+    out.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .nfc()
+        .collect()
+}
+
+/// Splits a BibTeX name list on the word "and", where it is not in braces.
+fn split_bibtex_names(s: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let bytes = s.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'{' => depth += 1,
+            b'}' => depth -= 1,
+            b'a' | b'A' if depth == 0
+                && i > start
+                && bytes[i - 1].is_ascii_whitespace()
+                && s[i..].get(..3).is_some_and(|w| w.eq_ignore_ascii_case("and"))
+                && bytes.get(i + 3).is_some_and(u8::is_ascii_whitespace) => {
+                names.push(&s[start..i]);
+                start = i + 3;
+            },
+            _ => {},
+        }
+    }
+    names.push(&s[start..]);
+    names.into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// A parser for BibTeX files. Only the common features are supported: field
+/// values may be in braces or quotes, or may be numbers or `@string` macros,
+/// and may be concatenated with `#`.
+struct BibtexParser<'a> {
+    src: &'a str,
+    pos: usize,
+    macros: HashMap<String, String>,
+}
+
+impl <'a> BibtexParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+    
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(d) if d == c => {
+                self.pos += c.len_utf8();
+                Ok(())
+            },
+            Some(d) => Err(format!("expected '{c}', was '{d}'")),
+            None => Err(format!("expected '{c}', was end of file")),
+        }
+    }
+    
+    fn identifier(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.src[self.pos..];
+        let len = rest.find(|c: char| c.is_whitespace() || matches!(c, '{' | '}' | '(' | ')' | ',' | '=' | '#' | '"'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+    
+    /// Reads text up to the closing delimiter, where braces are balanced. The
+    /// opening delimiter must already have been consumed.
+    fn delimited(&mut self, close: char) -> Result<&'a str, String> {
+        let start = self.pos;
+        let mut depth = 0;
+        for (i, c) in self.src[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                c if c == close && depth == 0 => {
+                    self.pos = start + i + c.len_utf8();
+                    return Ok(&self.src[start..start + i]);
+                },
+                _ => {},
+            }
+        }
+        Err(format!("unmatched '{}'", if close == '"' { '"' } else { '{' }))
+    }
+    
+    fn value(&mut self) -> Result<String, String> {
+        let mut value = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('{') => {
+                    self.pos += 1;
+                    value += self.delimited('}')?;
+                },
+                Some('"') => {
+                    self.pos += 1;
+                    value += self.delimited('"')?;
+                },
+                _ => {
+                    let word = self.identifier();
+                    if word.is_empty() {
+                        return Err("expected field value".to_string());
+                    }
+                    let word = word.to_lowercase();
+                    value += self.macros.get(&word).map_or(word.as_str(), String::as_str);
+                },
+            }
+            self.skip_whitespace();
+            if self.peek() == Some('#') {
+                self.pos += 1;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+    
+    fn parse(mut self) -> Result<Vec<(String, BibEntry)>, String> {
+        let mut entries = Vec::new();
+        while let Some(i) = self.src[self.pos..].find('@') {
+            self.pos += i + 1;
+            let start = self.pos;
+            let kind = self.identifier().to_lowercase();
+            self.skip_whitespace();
+            let close = match self.peek() {
+                _ if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) => None,
+                Some('{') => Some('}'),
+                Some('(') => Some(')'),
+                _ => None,
+            };
+            let Some(close) = close else {
+                // text outside of entries is a comment, which may contain '@'
+                self.pos = start;
+                continue;
+            };
+            self.pos += 1;
+            
+            match kind.as_str() {
+                "comment" | "preamble" => {
+                    self.delimited(close)?;
+                },
+                "string" => {
+                    let name = self.identifier().to_lowercase();
+                    self.expect('=')?;
+                    let value = self.value()?;
+                    self.macros.insert(name, value);
+                    self.expect(close)?;
+                },
+                _ => {
+                    let key = self.identifier().to_string();
+                    if key.is_empty() {
+                        return Err(format!("expected key after '@{kind}'"));
+                    }
+                    let mut fields = HashMap::new();
+                    loop {
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(',') => self.pos += 1,
+                            Some(c) if c == close => {
+                                self.pos += 1;
+                                break;
+                            },
+                            Some(_) => {
+                                let name = self.identifier().to_lowercase();
+                                self.expect('=')
+                                    .map_err(|e| format!("{e} in entry '{key}'"))?;
+                                let value = self.value()
+                                    .map_err(|e| format!("{e} in entry '{key}'"))?;
+                                fields.insert(name, value);
+                            },
+                            None => return Err(format!("unexpected end of file in entry '{key}'")),
+                        }
+                    }
+                    entries.push((key, bibtex_entry(fields)));
+                },
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Converts the fields of a BibTeX entry into a bibliography entry.
+fn bibtex_entry(mut fields: HashMap<String, String>) -> BibEntry {
+    let authors = fields.remove("author")
+        .or_else(|| fields.remove("editor"))
+        .map_or_else(Vec::new, |names| split_bibtex_names(&names).into_iter()
+            .map(Name::parse_bibtex)
+            .collect()
+        );
+    let mut field = |names: &[&str]| names.iter()
+        .find_map(|&name| fields.remove(name))
+        .map(|v| clean_latex(&v))
+        .filter(|v| !v.is_empty());
+    
+    BibEntry {
+        authors,
+        year: field(&["year"]).or_else(|| field(&["date"]).map(|d| d.chars().take(4).collect())),
+        title: field(&["title"]),
+        container: field(&["journal", "booktitle"]),
+        volume: field(&["volume"]),
+        issue: field(&["number"]),
+        pages: field(&["pages"]),
+        publisher: field(&["publisher", "institution", "school", "organization"]),
+        url: field(&["url"]),
+        doi: field(&["doi"]),
+    }
+}
+
+/// Parses the entries of a BibTeX file.
+fn parse_bibtex(src: &str) -> Result<Vec<(String, BibEntry)>, String> {
+    BibtexParser {src, pos: 0, macros: HashMap::new()}.parse()
+}
+
+/// Parses the entries of a CSL-JSON file, which is an array of objects.
+fn parse_csl_json(json: serde_json::Value) -> Result<Vec<(String, BibEntry)>, String> {
+    use serde_json::Value as JSON;
+    
+    fn string(v: Option<&JSON>) -> Option<String> {
+        match v? {
+            JSON::String(s) if !s.is_empty() => Some(s.clone()),
+            JSON::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+    
+    let JSON::Array(items) = json else {
+        return Err("expected an array of entries".to_string());
+    };
+    let mut entries = Vec::new();
+    for item in items {
+        let JSON::Object(obj) = item else {
+            return Err("expected an object for each entry".to_string());
+        };
+        let key = string(obj.get("id"))
+            .ok_or("expected an 'id' for each entry")?;
+        
+        let authors = match obj.get("author").or_else(|| obj.get("editor")) {
+            Some(JSON::Array(names)) => names.iter()
+                .map(|name| match string(name.get("family")) {
+                    Some(family) => Name {family, given: string(name.get("given"))},
+                    None => Name {family: string(name.get("literal")).unwrap_or_default(), given: None},
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let issued = obj.get("issued");
+        let year = issued
+            .and_then(|d| d.get("date-parts"))
+            .and_then(|d| d.get(0))
+            .and_then(|d| string(d.get(0)))
+            .or_else(|| issued.and_then(|d| string(d.get("literal").or_else(|| d.get("raw")))));
+        
+        entries.push((key, BibEntry {
+            authors,
+            year,
+            title: string(obj.get("title")),
+            container: string(obj.get("container-title")),
+            volume: string(obj.get("volume")),
+            issue: string(obj.get("issue")),
+            pages: string(obj.get("page")),
+            publisher: string(obj.get("publisher")),
+            url: string(obj.get("URL")),
+            doi: string(obj.get("DOI")),
+        }));
+    }
+    Ok(entries)
+}
+
+impl <'a> Compiler<'a> {
+    /// Loads the entries from a bibliography file, which is parsed as
+    /// CSL-JSON if it has a `.json` extension, or otherwise as BibTeX. Entries
+    /// with the same key as an already-loaded entry replace it.
+    pub(super) fn load_bibliography(&mut self, path: &Path, text: &str) -> errors::PapyriResult {
+        let entries = if path.extension().is_some_and(|ext| ext == "json") {
+            let json = serde_json::from_str(text)
+                .map_err(errors::RuntimeError::JsonParseError)?;
+            parse_csl_json(json)
+        } else {
+            parse_bibtex(text)
+        };
+        let entries = entries.map_err(errors::RuntimeError::BibliographyParseError)?;
+        
+        for (key, entry) in entries {
+            self.ctx.bibliography.insert(key.into(), Rc::new(entry));
+        }
+        Ok(())
+    }
+    
+    /// Creates a placeholder for an in-text citation of the given entries,
+    /// which is rendered when the document is complete. The keys must refer
+    /// to entries which have already been loaded. The locator, such as a page
+    /// number, may be empty.
+    pub(super) fn cite_impl(&mut self, keys: Vec<RcStr>, locator: HTML) -> errors::PapyriResult<Tag> {
+        if let Some(key) = keys.iter().find(|k| !self.ctx.bibliography.contains_key(*k)) {
+            let e = errors::RuntimeError::UnknownCitationKey(key.clone());
+            return Err(e.into());
+        }
+        
+        let content = keys.into_iter()
+            .map(|key| Tag::new(str_ids::CITE, HTML::Empty)
+                .str_attr(str_ids::NAME, &key)
+                .into())
+            .chain(std::iter::once(locator))
+            .collect();
+        Ok(Tag::new(str_ids::_CITE, content))
+    }
+}
+
+impl Context {
+    /// Replaces the citation and bibliography placeholders created by
+    /// `@cite` and `@bibliography` in the given document. The citation
+    /// style is given by the first `@bibliography`; if there is none, the
+    /// author-year style is used, and citations are not linked.
+    ///
+    /// If there are no placeholders, the document is returned unchanged.
+    pub(super) fn resolve_citations(&mut self, html: HTML) -> HTML {
+        if !html.contains_tag(str_ids::_CITE) && !html.contains_tag(str_ids::_BIBLIOGRAPHY) {
+            return html;
+        }
+        
+        let mut keys = Vec::new();
+        let mut style = None;
+        collect_citations(&html, &mut keys, &mut style);
+        let has_bibliography = style.is_some();
+        let style = style.unwrap_or(CitationStyle::AuthorYear);
+        
+        let mut cited: Vec<_> = keys.into_iter()
+            .filter_map(|key| self.bibliography.get(&key).map(|entry| (key, entry.clone())))
+            .collect();
+        if style == CitationStyle::AuthorYear {
+            cited.sort_by(|(_, a), (_, b)| a.sort_key().cmp(&b.sort_key()));
+        }
+        
+        let mut ids = HashMap::new();
+        if has_bibliography {
+            self.reserve_ids(&html);
+            for (key, _) in cited.iter() {
+                let id = self.unique_ids.get_unique_id(&format!("bib_{key}"), MAX_ID_LENGTH);
+                ids.insert(key.clone(), id);
+            }
+        }
+        let citations = Citations {style, cited, ids};
+        citations.replace(&html)
+    }
+}
+
+/// Finds the keys of the cited entries in the given HTML, in the order they
+/// are first cited, and the style of the first bibliography.
+fn collect_citations(html: &HTML, keys: &mut Vec<RcStr>, style: &mut Option<CitationStyle>) {
+    match html {
+        HTML::Tag(tag) if tag.name_id == str_ids::_BIBLIOGRAPHY && style.is_none() => {
+            *style = tag.get_attr(str_ids::STYLE)
+                .flatten()
+                .and_then(CitationStyle::from_name);
+        },
+        HTML::Tag(tag) if tag.name_id == str_ids::_CITE => {
+            for key in tag.content.nodes().iter().filter_map(cite_key) {
+                if !keys.iter().any(|k| k.as_ref() == key) {
+                    keys.push(key.into());
+                }
+            }
+        },
+        HTML::Tag(tag) => collect_citations(&tag.content, keys, style),
+        HTML::Sequence(seq) => {
+            for child in seq.iter() {
+                collect_citations(child, keys, style);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// The entries cited in a document, in the order they appear in its
+/// bibliography.
+struct Citations {
+    style: CitationStyle,
+    cited: Vec<(RcStr, Rc<BibEntry>)>,
+    
+    /// The IDs of the bibliography entries, if the document has a
+    /// bibliography.
+    ids: HashMap<RcStr, Rc<str>>,
+}
+
+impl Citations {
+    fn replace(&self, html: &HTML) -> HTML {
+        match html {
+            HTML::Tag(tag) if tag.name_id == str_ids::_CITE => self.citation(tag),
+            HTML::Tag(tag) if tag.name_id == str_ids::_BIBLIOGRAPHY => self.bibliography(),
+            HTML::Tag(tag) if tag.content.contains_tag(str_ids::_CITE) || tag.content.contains_tag(str_ids::_BIBLIOGRAPHY) => {
+                let mut new_tag = Tag::clone(tag);
+                new_tag.content = self.replace(&tag.content);
+                new_tag.into()
+            },
+            HTML::Sequence(seq) => seq.iter()
+                .map(|child| self.replace(child))
+                .collect(),
+            _ => html.clone(),
+        }
+    }
+    
+    /// Renders an in-text citation, such as "(Smith 2020; Jones 2019)" or
+    /// "[1, 2]", followed by the locator if there is one. Each cited entry
+    /// links to its bibliography entry, if there is one.
+    fn citation(&self, tag: &Tag) -> HTML {
+        let (open, sep, close) = match self.style {
+            CitationStyle::AuthorYear => ("(", "; ", ")"),
+            CitationStyle::Numeric => ("[", ", ", "]"),
+        };
+        let mut parts = vec![HTML::text(open)];
+        for (i, key) in tag.content.nodes().iter().filter_map(cite_key).enumerate() {
+            let Some(index) = self.cited.iter().position(|(k, _)| k.as_ref() == key) else {
+                continue;
+            };
+            if i > 0 { parts.push(HTML::text(sep)); }
+            
+            let text = match self.style {
+                CitationStyle::AuthorYear => self.cited[index].1.author_year(),
+                CitationStyle::Numeric => (index + 1).to_string(),
+            };
+            parts.push(match self.ids.get(key) {
+                Some(id) => Tag::new(str_ids::A, HTML::text(text))
+                    .str_attr(str_ids::HREF, &format!("#{id}"))
+                    .into(),
+                None => HTML::text(text),
+            });
+        }
+        let locator: HTML = tag.content.nodes()
+            .iter()
+            .filter(|node| cite_key(node).is_none())
+            .cloned()
+            .collect();
+        if !locator.is_whitespace() {
+            parts.push(HTML::text(", "));
+            parts.push(locator);
+        }
+        parts.push(HTML::text(close));
+        Tag::new(str_ids::SPAN, parts.into_iter().collect())
+            .str_attr(str_ids::CLASS, "citation")
+            .into()
+    }
+    
+    /// Renders a bibliography section containing the cited entries.
+    fn bibliography(&self) -> HTML {
+        if self.cited.is_empty() {
+            return HTML::Empty;
+        }
+        let items = self.cited.iter()
+            .enumerate()
+            .map(|(i, (key, entry))| {
+                let content = match self.style {
+                    CitationStyle::AuthorYear => entry.to_html(),
+                    CitationStyle::Numeric => [HTML::text(format!("[{}] ", i + 1)), entry.to_html()].into_iter().collect(),
+                };
+                let mut p = Tag::new(str_ids::P, content);
+                if let Some(id) = self.ids.get(key) {
+                    p = p.str_attr(str_ids::ID, id);
+                }
+                p.into()
+            })
+            .collect();
+        Tag::new(str_ids::SECTION, items)
+            .str_attr(str_ids::CLASS, "bibliography")
+            .into()
+    }
+}
+
+/// Returns the key of a cited entry in a citation placeholder.
+fn cite_key(html: &HTML) -> Option<&str> {
+    match html {
+        HTML::Tag(tag) if tag.name_id == str_ids::CITE => tag.get_attr(str_ids::NAME).flatten(),
+        _ => None,
+    }
+}
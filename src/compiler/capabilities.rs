@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use crate::errors;
+use super::base::Compiler;
+
+#[derive(Debug, Clone)]
+/// Controls which potentially-unsafe operations Papyri source files compiled
+/// in a context are allowed to perform. By default, everything is allowed;
+/// use `Capabilities::sandboxed` when compiling untrusted source files.
+pub struct Capabilities {
+    /// Whether `@file::read` may be used to read files.
+    pub file_read: bool,
+    
+    /// Whether `@file::list` may be used to list files in a directory.
+    pub file_list: bool,
+    
+    /// Whether `@file::write` may be used to write output files.
+    pub file_write: bool,
+    
+    /// Whether the `@fetch` functions may be used to make network requests.
+    pub fetch: bool,
+    
+    /// If set, `@import`, `@include`, `@file::read` and `@file::list` may only
+    /// access paths within this directory.
+    pub root: Option<PathBuf>,
+}
+
+impl Default for Capabilities {
+    fn default() -> Capabilities {
+        Capabilities {
+            file_read: true,
+            file_list: true,
+            file_write: true,
+            fetch: true,
+            root: None,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Returns a set of capabilities which allows no file access except for
+    /// imports within the given root directory, and no network requests.
+    pub fn sandboxed<P: Into<PathBuf>>(root: P) -> Capabilities {
+        Capabilities {
+            file_read: false,
+            file_list: false,
+            file_write: false,
+            fetch: false,
+            root: Some(root.into()),
+        }
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Returns an error if the given capability is not allowed in this
+    /// compiler's context. The name is the name of the native function which
+    /// requires the capability.
+    pub(super) fn require_capability(&self, allowed: fn(&Capabilities) -> bool, func_name: &'static str) -> errors::PapyriResult {
+        if allowed(&self.ctx.capabilities) {
+            Ok(())
+        } else {
+            let e = errors::RuntimeError::NotAllowed(func_name);
+            Err(e.into())
+        }
+    }
+    
+    /// Returns an error if the given path is not within the root directory
+    /// allowed by this compiler's context. Paths which do not exist are not
+    /// checked here, since accessing them will fail anyway.
+    pub(super) fn require_path_in_root(&self, path: &Path, path_str: &str) -> errors::PapyriResult {
+        let Some(root) = &self.ctx.capabilities.root else {
+            return Ok(());
+        };
+        let fs = self.ctx.file_system.as_ref();
+        let Ok(path) = fs.canonicalize(path) else {
+            return Ok(());
+        };
+        if fs.canonicalize(root).is_ok_and(|root| path.starts_with(root)) {
+            Ok(())
+        } else {
+            let e = errors::RuntimeError::PathNotInRoot(path_str.into());
+            Err(e.into())
+        }
+    }
+}
@@ -2,12 +2,19 @@ use std::rc::Rc;
 
 use crate::errors;
 use crate::utils::{OutFiles, NameID, StringPool, text};
+use crate::utils::filesystem::{FileSystem, OsFileSystem};
 use crate::utils::sourcefile::{SourceRange, SourceFileCache, SourceFile};
-use super::base::Compiler;
+use super::base::{Compiler, CompileResult, Document};
+use super::bibliography::Bibliography;
+use super::capabilities::Capabilities;
+use super::fetch::FetchOptions;
 use super::frame::InactiveFrame;
 use super::html::HTML;
+use super::limits::{Budget, Limits};
 use super::module_loader::ModuleCache;
 use super::native::NativeDefs;
+use super::render::OutFile;
+use super::testing::TestOutcome;
 use super::value::RcStr;
 
 /// Holds the context for a compilation job.
@@ -33,8 +40,42 @@ pub struct Context {
     /// The unique ID generator for this compiler context.
     pub(super) unique_ids: text::UniqueIDGenerator,
     
+    /// The bibliography entries loaded by `@bib::load` in the current
+    /// compile job.
+    pub(super) bibliography: Bibliography,
+    
+    /// The file system which source files and data files are read from, and
+    /// output files are written to. This is the operating system's file
+    /// system by default.
+    pub file_system: Rc<dyn FileSystem>,
+    
+    /// Controls which file and network operations are allowed in this
+    /// compiler context.
+    pub capabilities: Capabilities,
+    
+    /// Controls how the `@fetch` functions make network requests, including
+    /// caching and offline mode.
+    pub fetch_options: FetchOptions,
+    
+    /// Limits on the work done when compiling in this context.
+    pub limits: Limits,
+    
+    /// Tracks the work done in the current compilation job, against the
+    /// limits.
+    pub(super) budget: Budget,
+    
     /// The output files collector for this compiler context, if it has one.
-    pub out_files: Option<OutFiles<HTML>>,
+    pub out_files: Option<OutFiles<OutFile>>,
+    
+    /// The output path of the page currently being compiled, relative to the
+    /// output directory. Assets copied by `@asset::url` are written relative
+    /// to this page's directory, or to the output directory if this is `None`.
+    pub page_path: Option<std::path::PathBuf>,
+    
+    /// The outcomes of test cases declared by the `@test::ok` and `@test::err`
+    /// functions, if this context is running tests. Test cases cannot be
+    /// declared if this is `None`.
+    pub tests: Option<Vec<TestOutcome>>,
 }
 
 impl Context {
@@ -51,7 +92,15 @@ impl Context {
             natives,
             natives_frame,
             unique_ids: text::UniqueIDGenerator::new(),
+            bibliography: Bibliography::new(),
+            file_system: Rc::new(OsFileSystem),
+            capabilities: Capabilities::default(),
+            fetch_options: FetchOptions::default(),
+            limits: Limits::default(),
+            budget: Budget::default(),
             out_files: out_dir.map(OutFiles::new),
+            page_path: None,
+            tests: None,
         };
         ctx.compile_stdlib();
         ctx
@@ -60,7 +109,11 @@ impl Context {
     /// Adds an output file to this context's collector. The operation may fail
     /// if this context has no output file collector, or if the path is not
     /// within the output directory.
-    pub(super) fn push_out_file(&mut self, path: RcStr, content: HTML) -> errors::PapyriResult {
+    pub(super) fn push_out_file(&mut self, path: RcStr, content: OutFile) -> errors::PapyriResult {
+        let content = match content {
+            OutFile::HTML(html, mode) => OutFile::HTML(self.resolve_deferred(html), mode),
+            content => content,
+        };
         let Some(sink) = self.out_files.as_mut() else {
             let e = errors::RuntimeError::WriteFileNotAllowed;
             return Err(e.into());
@@ -73,11 +126,86 @@ impl Context {
         }
     }
     
+    /// Replaces the placeholders in a compiled document for content which
+    /// depends on the whole document, such as footnotes, citations,
+    /// cross-references and tables of contents.
+    pub(super) fn resolve_deferred(&mut self, html: HTML) -> HTML {
+        let html = self.resolve_footnotes(html);
+        let html = self.resolve_citations(html);
+        let html = self.resolve_labels(html);
+        self.resolve_toc(html)
+    }
+    
+    /// Compiles Papyri source given as a string, returning the HTML tree, the
+    /// exported values and any diagnostics which were reported. Unlike
+    /// `compile_str`, the output is returned even if there were errors, so it
+    /// may be incomplete.
+    /// 
+    /// Each `@ref` to a label which is not in the document is left as a
+    /// placeholder, so that it can be resolved by `CrossReferences`.
+    pub fn compile_document(&mut self, src: &str) -> Document {
+        let result = self.compile_synthetic(src);
+        Document {
+            html: result.out,
+            exports: result.exports.into(),
+            diagnostics: self.diagnostics.take(),
+        }
+    }
+    
+    /// Compiles Papyri source given as a string into HTML, as a string. If any
+    /// errors or warnings occur during compilation, the diagnostics are
+    /// returned instead.
+    /// 
+    /// Since the source is not part of a build, a warning is reported for
+    /// each `@ref` to a label which is not in the source.
+    pub fn compile_str(&mut self, src: &str) -> Result<String, errors::Diagnostics> {
+        let result = self.compile_synthetic(src);
+        self.report_unknown_labels(&result.out);
+        let diagnostics = self.diagnostics.take();
+        
+        if diagnostics.is_empty() {
+            let mut out = Vec::new();
+            self.render(&result.out, true, &mut out)
+                .unwrap();
+            
+            let out = String::from_utf8(out).unwrap();
+            Ok(out)
+        } else {
+            Err(diagnostics)
+        }
+    }
+    
+    fn compile_synthetic(&mut self, src: &str) -> CompileResult {
+        let src = self.source_files.load_synthetic("<string>", src);
+        self.compile(src)
+    }
+    
+    /// Returns the name associated with an interned name ID, such as a tag
+    /// name, an attribute name or a dictionary key.
+    pub fn get_name(&self, name_id: NameID) -> RcStr {
+        self.string_pool.get(name_id)
+    }
+    
+    /// Returns the interned name ID for the given name, if it has one. Names
+    /// which have no ID do not occur in any tag or dictionary.
+    pub fn get_name_id(&self, name: &str) -> Option<NameID> {
+        self.string_pool.get_id_if_present(name)
+    }
+    
+    /// Renders the contents of an output file and writes it to this context's
+    /// file system.
+    pub fn write_out_file(&self, path: &std::path::Path, file: &OutFile, as_html: bool) -> std::io::Result<()> {
+        let mut out = Vec::new();
+        self.render_out_file(file, as_html, &mut out)?;
+        self.file_system.write(path, &out)
+    }
+    
     /// Clears any state from the previous compile job. Any `out_files` must
     /// already have been handled before calling this method.
     pub fn reset(&mut self) {
         self.diagnostics.clear();
         self.unique_ids.clear();
+        self.bibliography.clear();
         if matches!(&self.out_files, Some(o) if !o.is_empty()) {
             errors::ice("Output files were not consumed");
         }
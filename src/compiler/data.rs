@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+
+use crate::errors;
+use crate::utils::text;
+use super::base::Compiler;
+use super::html::HTML;
+use super::value::{Value, Dict};
+
+/// Converts a key from a data file into a dictionary key which can be accessed
+/// from Papyri source. Keys which are already valid identifiers are unchanged;
+/// otherwise, non-ASCII letters are transliterated, each run of other
+/// characters which are not letters, digits or underscores is replaced with a
+/// single underscore, and leading or trailing underscores are removed. If the
+/// result is empty or begins with a digit, it is prefixed with `key_`.
+///
+/// For example, `release-date` and `Release Date` become `release_date` and
+/// `Release_Date`, `Hôpital` becomes `Hopital`, and `2023` becomes `key_2023`.
+pub(super) fn data_key_to_name(key: &str) -> Cow<'_, str> {
+    if text::is_identifier(key) {
+        return Cow::Borrowed(key);
+    }
+
+    let mut name = String::new();
+    for c in deunicode::deunicode(key).chars() {
+        if text::is_ident_cont(c) {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_matches('_');
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        Cow::Owned(format!("key_{name}"))
+    } else {
+        Cow::Owned(name.to_string())
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Creates a dictionary from the entries of a data file, mapping the keys
+    /// using `data_key_to_name`. It is an error if two keys map to the same
+    /// name.
+    fn data_dict<I: IntoIterator<Item=(String, Value)>>(&mut self, entries: I) -> errors::PapyriResult<Value> {
+        let mut dict = Dict::default();
+        for (k, v) in entries {
+            let name = data_key_to_name(&k);
+            let name_id = self.string_pool_mut().insert(name.as_ref());
+            if dict.insert(name_id, v).is_some() {
+                let e = errors::RuntimeError::DataKeyCollision(name.into());
+                return Err(e.into());
+            }
+        }
+        Ok(dict.into())
+    }
+
+    /// Parses the given string as JSON, and converts the result to a Papyri
+    /// value.
+    pub(super) fn parse_json(&mut self, text: &str) -> errors::PapyriResult<Value> {
+        let json = serde_json::from_str(text)
+            .map_err(errors::RuntimeError::JsonParseError)?;
+        self.json_to_value(json)
+    }
+
+    /// Converts a JSON value to a Papyri value. Objects become dictionaries,
+    /// with keys mapped by `data_key_to_name`. `null` becomes the unit value,
+    /// and numbers which are not 64-bit integers become strings.
+    fn json_to_value(&mut self, json: serde_json::Value) -> errors::PapyriResult<Value> {
+        let v = match json {
+            serde_json::Value::Null => Value::UNIT,
+            serde_json::Value::Bool(b) => b.into(),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => i.into(),
+                None => n.to_string().into(),
+            },
+            serde_json::Value::String(s) => s.into(),
+            serde_json::Value::Array(vs) => vs.into_iter()
+                .map(|v| self.json_to_value(v))
+                .collect::<errors::PapyriResult<Vec<Value>>>()?
+                .into(),
+            serde_json::Value::Object(vs) => {
+                let entries = vs.into_iter()
+                    .map(|(k, v)| Ok((k, self.json_to_value(v)?)))
+                    .collect::<errors::PapyriResult<Vec<_>>>()?;
+                self.data_dict(entries)?
+            },
+        };
+        Ok(v)
+    }
+
+    /// Encodes the given value as JSON text. Dictionary keys are written as
+    /// they are, without reversing the mapping applied when data files are
+    /// read.
+    pub(super) fn encode_json(&mut self, value: Value, pretty: bool) -> errors::PapyriResult<String> {
+        let json = self.value_to_json(value)?;
+        let text = if pretty {
+            serde_json::to_string_pretty(&json)
+        } else {
+            serde_json::to_string(&json)
+        };
+        Ok(text.unwrap_or_else(|e| errors::ice(&format!("Failed to encode JSON: {e}"))))
+    }
+    
+    /// Converts a Papyri value to a JSON value. HTML content is rendered as a
+    /// string of HTML, except that the unit value becomes `null`. Functions
+    /// and regexes cannot be converted.
+    fn value_to_json(&mut self, value: Value) -> errors::PapyriResult<serde_json::Value> {
+        let json = match value {
+            Value::Bool(b) => b.into(),
+            Value::Int(i) => i.into(),
+            Value::Str(s) => s.as_ref().into(),
+            Value::List(vs) => vs.as_ref()
+                .iter()
+                .map(|v| self.value_to_json(v.clone()))
+                .collect::<errors::PapyriResult<Vec<_>>>()?
+                .into(),
+            Value::Dict(vs) => vs.iter()
+                .map(|(&k, v)| Ok((self.get_name(k).to_string(), self.value_to_json(v.clone())?)))
+                .collect::<errors::PapyriResult<serde_json::Map<_, _>>>()?
+                .into(),
+            Value::HTML(HTML::Empty) => serde_json::Value::Null,
+            Value::HTML(html) => self.escape_html_impl(html).into(),
+            Value::Func(..) | Value::Regex(..) => {
+                let e = errors::TypeError::NotJsonSerializable(value.get_type());
+                return Err(e.into());
+            },
+        };
+        Ok(json)
+    }
+    
+    /// Parses the given string as TOML, and converts the result to a Papyri
+    /// dictionary.
+    pub(super) fn parse_toml(&mut self, text: &str) -> errors::PapyriResult<Value> {
+        let toml = toml::from_str(text)
+            .map_err(errors::RuntimeError::TomlParseError)?;
+        self.toml_to_value(toml)
+    }
+
+    /// Converts a TOML value to a Papyri value. Tables become dictionaries,
+    /// with keys mapped by `data_key_to_name`. Floats and dates become strings.
+    fn toml_to_value(&mut self, toml: toml::Value) -> errors::PapyriResult<Value> {
+        let v = match toml {
+            toml::Value::Boolean(b) => b.into(),
+            toml::Value::Integer(i) => i.into(),
+            toml::Value::Float(f) => f.to_string().into(),
+            toml::Value::Datetime(d) => d.to_string().into(),
+            toml::Value::String(s) => s.into(),
+            toml::Value::Array(vs) => vs.into_iter()
+                .map(|v| self.toml_to_value(v))
+                .collect::<errors::PapyriResult<Vec<Value>>>()?
+                .into(),
+            toml::Value::Table(vs) => {
+                let entries = vs.into_iter()
+                    .map(|(k, v)| Ok((k, self.toml_to_value(v)?)))
+                    .collect::<errors::PapyriResult<Vec<_>>>()?;
+                self.data_dict(entries)?
+            },
+        };
+        Ok(v)
+    }
+
+    /// Parses the given string as CSV, and converts the result to a list of
+    /// dictionaries, one per row. The first row is the header row, which gives
+    /// the dictionary keys, mapped by `data_key_to_name`. All values are
+    /// strings.
+    pub(super) fn parse_csv(&mut self, text: &str) -> errors::PapyriResult<Value> {
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = reader.headers()
+            .map_err(errors::RuntimeError::CsvParseError)?
+            .clone();
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(errors::RuntimeError::CsvParseError)?;
+            let entries = headers.iter()
+                .zip(record.iter())
+                .map(|(k, v)| (k.to_string(), v.into()));
+            rows.push(self.data_dict(entries)?);
+        }
+        Ok(rows.into())
+    }
+}
@@ -0,0 +1,259 @@
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use reqwest::blocking::Client;
+use reqwest::{header, Method, StatusCode};
+
+use crate::errors;
+use crate::utils::filesystem::FileSystem;
+use super::base::Compiler;
+use super::value::{Int, RcStr, RcDict};
+
+#[derive(Debug, Clone)]
+/// Controls how the `@fetch` functions make network requests.
+pub struct FetchOptions {
+    /// The directory in which fetched responses are cached. Responses are not
+    /// cached if this is `None`.
+    pub cache_dir: Option<PathBuf>,
+    
+    /// How long a cached response may be used before it must be revalidated.
+    pub cache_ttl: Duration,
+    
+    /// The timeout for each network request, if any.
+    pub timeout: Option<Duration>,
+    
+    /// If true, responses are only served from the cache, and no network
+    /// requests are made. A cache miss is an error.
+    pub offline: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> FetchOptions {
+        FetchOptions {
+            cache_dir: None,
+            cache_ttl: Duration::from_secs(24 * 60 * 60),
+            timeout: Some(Duration::from_secs(30)),
+            offline: false,
+        }
+    }
+}
+
+/// A network request made by one of the `@fetch` functions.
+pub(super) struct FetchRequest {
+    url: RcStr,
+    method: Method,
+    headers: Vec<(String, RcStr)>,
+    body: Option<RcStr>,
+    expected_status: Option<Int>,
+}
+
+impl FetchRequest {
+    /// Only `GET` requests are served from the cache; requests with different
+    /// headers are cached separately.
+    fn is_cacheable(&self) -> bool {
+        self.method == Method::GET
+    }
+    
+    fn header_lines(&self) -> Vec<String> {
+        self.headers.iter()
+            .map(|(k, v)| format!("{k}: {v}"))
+            .collect()
+    }
+}
+
+/// A response held in the fetch cache. The body is stored in a separate file
+/// from the metadata.
+struct CachedResponse {
+    url: String,
+    headers: Vec<String>,
+    status: u16,
+    etag: Option<String>,
+    fetched: SystemTime,
+    body: String,
+}
+
+/// The location of an entry in the fetch cache. Entries are addressed by a
+/// hash of the request; the URL and request headers are also stored, so that
+/// collisions are treated as cache misses.
+struct CacheEntry {
+    meta_path: PathBuf,
+    body_path: PathBuf,
+}
+
+impl CacheEntry {
+    fn new(cache_dir: &Path, request: &FetchRequest) -> CacheEntry {
+        let mut hasher = fxhash::FxHasher64::default();
+        hasher.write(request.url.as_bytes());
+        for line in request.header_lines() {
+            hasher.write(line.as_bytes());
+        }
+        let key = format!("{:016x}", hasher.finish());
+        CacheEntry {
+            meta_path: cache_dir.join(format!("{key}.meta")),
+            body_path: cache_dir.join(format!("{key}.body")),
+        }
+    }
+    
+    /// Reads the cached response for the given request. Any failure to read
+    /// the entry is treated as a cache miss.
+    fn read(&self, fs: &dyn FileSystem, request: &FetchRequest) -> Option<CachedResponse> {
+        let meta = fs.read_to_string(&self.meta_path).ok()?;
+        let mut response = CachedResponse {
+            url: String::new(),
+            headers: Vec::new(),
+            status: 200,
+            etag: None,
+            fetched: SystemTime::UNIX_EPOCH,
+            body: String::new(),
+        };
+        for line in meta.lines() {
+            match line.split_once(' ') {
+                Some(("url", v)) => response.url = v.to_string(),
+                Some(("header", v)) => response.headers.push(v.to_string()),
+                Some(("status", v)) => response.status = v.parse().ok()?,
+                Some(("etag", v)) => response.etag = Some(v.to_string()),
+                Some(("fetched", v)) => response.fetched += Duration::from_secs(v.parse().ok()?),
+                _ => {},
+            }
+        }
+        if response.url != request.url.as_ref() || response.headers != request.header_lines() {
+            return None;
+        }
+        
+        response.body = fs.read_to_string(&self.body_path).ok()?;
+        Some(response)
+    }
+    
+    fn write(&self, fs: &dyn FileSystem, response: &CachedResponse, write_body: bool) -> io::Result<()> {
+        if write_body {
+            fs.write(&self.body_path, response.body.as_bytes())?;
+        }
+        
+        let fetched = response.fetched
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut meta = format!("url {}\n", response.url);
+        for header in &response.headers {
+            meta += &format!("header {header}\n");
+        }
+        meta += &format!("status {}\nfetched {fetched}\n", response.status);
+        if let Some(etag) = &response.etag {
+            meta += &format!("etag {etag}\n");
+        }
+        fs.write(&self.meta_path, meta.as_bytes())
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Builds a request from the arguments of a `@fetch` function. Underscores
+    /// in header names are replaced with hyphens, so that headers can be given
+    /// as named arguments, e.g. `content_type` for `Content-Type`.
+    pub(super) fn fetch_request(&self, url: RcStr, method: RcStr, headers: Option<RcDict>, body: Option<RcStr>, expected_status: Option<Int>) -> errors::PapyriResult<FetchRequest> {
+        let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+            .map_err(|_| errors::RuntimeError::FetchInvalidMethod(method))?;
+        
+        let mut header_list = Vec::new();
+        for (&k, v) in headers.iter().flat_map(|h| h.iter()) {
+            let name = self.get_name(k).replace('_', "-");
+            header_list.push((name, v.clone().try_convert()?));
+        }
+        
+        Ok(FetchRequest {
+            url,
+            method,
+            headers: header_list,
+            body,
+            expected_status,
+        })
+    }
+    
+    /// Makes the given request and returns the response body as text.
+    /// Responses are served from the cache if possible, according to this
+    /// compiler's fetch options.
+    pub(super) fn fetch_text(&mut self, request: &FetchRequest) -> errors::PapyriResult<String> {
+        let response = self.fetch_response(request)?;
+        match request.expected_status {
+            Some(expected) if expected != response.status as Int => {
+                let e = errors::RuntimeError::FetchUnexpectedStatus(request.url.clone(), expected, response.status);
+                Err(e.into())
+            },
+            _ => Ok(response.body),
+        }
+    }
+    
+    fn fetch_response(&mut self, request: &FetchRequest) -> errors::PapyriResult<CachedResponse> {
+        let options = &self.ctx.fetch_options;
+        let fs = self.ctx.file_system.as_ref();
+        let url = request.url.as_ref();
+        let entry = options.cache_dir
+            .as_ref()
+            .filter(|_| request.is_cacheable())
+            .map(|dir| CacheEntry::new(dir, request));
+        let cached = entry.as_ref()
+            .and_then(|entry| entry.read(fs, request));
+        
+        if options.offline {
+            return cached
+                .ok_or_else(|| errors::RuntimeError::FetchOffline(url.into()).into());
+        }
+        match cached {
+            Some(response) if response.fetched.elapsed().is_ok_and(|age| age < options.cache_ttl) => {
+                return Ok(response);
+            },
+            _ => {},
+        }
+        
+        let mut client = Client::builder()
+            .user_agent("Mozilla/5.0 (compatible) Papyri");
+        if let Some(timeout) = options.timeout {
+            client = client.timeout(timeout);
+        }
+        let mut http_request = client.build()
+            .map_err(errors::RuntimeError::NetworkError)?
+            .request(request.method.clone(), url);
+        for (k, v) in request.headers.iter() {
+            http_request = http_request.header(k, v.as_ref());
+        }
+        if let Some(body) = &request.body {
+            http_request = http_request.body(body.to_string());
+        }
+        if let Some(etag) = cached.as_ref().and_then(|r| r.etag.as_ref()) {
+            http_request = http_request.header(header::IF_NONE_MATCH, etag);
+        }
+        let response = http_request.send()
+            .map_err(errors::RuntimeError::NetworkError)?;
+        
+        let status = response.status();
+        let (response, write_body) = match cached {
+            Some(mut cached) if status == StatusCode::NOT_MODIFIED => {
+                cached.fetched = SystemTime::now();
+                (cached, false)
+            },
+            _ => {
+                let etag = response.headers()
+                    .get(header::ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                let body = response.text()
+                    .map_err(errors::RuntimeError::NetworkError)?;
+                let response = CachedResponse {
+                    url: url.to_string(),
+                    headers: request.header_lines(),
+                    status: status.as_u16(),
+                    etag,
+                    fetched: SystemTime::now(),
+                    body,
+                };
+                (response, true)
+            },
+        };
+        
+        if let Some(entry) = entry.filter(|_| status.is_success() || status == StatusCode::NOT_MODIFIED) {
+            entry.write(fs, &response, write_body)
+                .map_err(errors::RuntimeError::FetchCacheError)?;
+        }
+        Ok(response)
+    }
+}
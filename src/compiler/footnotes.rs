@@ -0,0 +1,124 @@
+use std::rc::Rc;
+
+use crate::utils::str_ids;
+use super::context::Context;
+use super::html::HTML;
+use super::tag::Tag;
+
+/// The maximum length of a generated footnote ID.
+const MAX_ID_LENGTH: usize = 32;
+
+/// A numbered footnote, waiting to be written in a footnotes section.
+struct Footnote {
+    number: usize,
+    id: Rc<str>,
+    ref_id: Rc<str>,
+    body: HTML,
+}
+
+/// Creates a footnotes section containing the given footnotes, each with a
+/// link back to where it was referenced.
+fn footnotes_section(footnotes: Vec<Footnote>) -> HTML {
+    let mut items = vec![HTML::tag(str_ids::HR, HTML::Empty)];
+    for f in footnotes {
+        let backref = Tag::new(str_ids::A, HTML::text(format!("[{}]", f.number)))
+            .str_attr(str_ids::HREF, &format!("#{}", f.ref_id))
+            .str_attr(str_ids::CLASS, "footnote-backref");
+        let content = [backref.into(), HTML::Whitespace, f.body];
+        items.push(Tag::new(str_ids::P, content.into_iter().collect())
+            .str_attr(str_ids::ID, &f.id)
+            .into());
+    }
+    Tag::new(str_ids::SECTION, items.into_iter().collect())
+        .str_attr(str_ids::CLASS, "footnotes")
+        .into()
+}
+
+/// Appends the given section to the end of the document's `<body>`, or to the
+/// end of the document if it has no `<body>`.
+fn append_to_body(html: HTML, section: HTML) -> HTML {
+    if !html.contains_tag(str_ids::BODY) {
+        return [html, section].into_iter().collect();
+    }
+    match html {
+        HTML::Tag(tag) => {
+            let mut new_tag = Tag::clone(&tag);
+            new_tag.content = if tag.name_id == str_ids::BODY {
+                [tag.content.clone(), section].into_iter().collect()
+            } else {
+                append_to_body(tag.content.clone(), section)
+            };
+            new_tag.into()
+        },
+        HTML::Sequence(seq) => {
+            let mut seq = seq.to_vec();
+            if let Some(child) = seq.iter_mut().find(|child| child.contains_tag(str_ids::BODY)) {
+                *child = append_to_body(child.clone(), section);
+            }
+            seq.into_iter().collect()
+        },
+        html => html,
+    }
+}
+
+impl Context {
+    /// Replaces the footnote placeholders created by `@footnotes::note` and
+    /// `@footnotes::render` in the given document. Footnotes are numbered in
+    /// the order they occur, and each `@footnotes::render` placeholder becomes
+    /// a section containing the footnotes since the previous one. Any
+    /// remaining footnotes are written in a section at the end of the page.
+    ///
+    /// If there are no placeholders, the document is returned unchanged.
+    pub(super) fn resolve_footnotes(&mut self, html: HTML) -> HTML {
+        if !html.contains_tag(str_ids::_FOOTNOTE) && !html.contains_tag(str_ids::_FOOTNOTES) {
+            return html;
+        }
+        
+        self.reserve_ids(&html);
+        let mut pending = Vec::new();
+        let mut count = 0;
+        let html = self.number_footnotes(&html, &mut pending, &mut count);
+        if pending.is_empty() {
+            html
+        } else {
+            append_to_body(html, footnotes_section(pending))
+        }
+    }
+    
+    fn number_footnotes(&mut self, html: &HTML, pending: &mut Vec<Footnote>, count: &mut usize) -> HTML {
+        match html {
+            HTML::Tag(tag) if tag.name_id == str_ids::_FOOTNOTE => {
+                *count += 1;
+                let number = *count;
+                let id = self.unique_ids.get_unique_id(&format!("footnote_{number}"), MAX_ID_LENGTH);
+                let ref_id = self.unique_ids.get_unique_id(&format!("footnote_ref_{number}"), MAX_ID_LENGTH);
+                
+                let link = Tag::new(str_ids::A, HTML::text(format!("[{number}]")))
+                    .str_attr(str_ids::HREF, &format!("#{id}"));
+                let reference = Tag::new(str_ids::SUP, link.into())
+                    .str_attr(str_ids::ID, &ref_id)
+                    .str_attr(str_ids::CLASS, "footnote-ref");
+                
+                // the body is numbered after the reference, so that footnotes
+                // within footnotes are numbered in reading order
+                let i = pending.len();
+                pending.push(Footnote {number, id, ref_id, body: HTML::Empty});
+                pending[i].body = self.number_footnotes(&tag.content, pending, count);
+                reference.into()
+            },
+            HTML::Tag(tag) if tag.name_id == str_ids::_FOOTNOTES => {
+                let footnotes = std::mem::take(pending);
+                if footnotes.is_empty() { HTML::Empty } else { footnotes_section(footnotes) }
+            },
+            HTML::Tag(tag) if tag.content.contains_tag(str_ids::_FOOTNOTE) || tag.content.contains_tag(str_ids::_FOOTNOTES) => {
+                let mut new_tag = Tag::clone(tag);
+                new_tag.content = self.number_footnotes(&tag.content, pending, count);
+                new_tag.into()
+            },
+            HTML::Sequence(seq) => seq.iter()
+                .map(|child| self.number_footnotes(child, pending, count))
+                .collect(),
+            _ => html.clone(),
+        }
+    }
+}
@@ -88,6 +88,20 @@ impl InactiveFrame {
             None,
         )
     }
+    
+    /// Gets the value of a variable declared in this frame, ignoring any
+    /// variables declared in its lexical parents.
+    pub(super) fn get_local(&self, name_id: NameID) -> Option<Value> {
+        let f = self.f.as_ref().borrow();
+        f.locals.get(&name_id).cloned()
+    }
+    
+    /// Sets the value of a variable in this frame. Returns `true` if a variable
+    /// of that name was already present.
+    pub(super) fn set(&self, name_id: NameID, value: Value) -> bool {
+        let mut f = self.f.as_ref().borrow_mut();
+        f.set(name_id, value)
+    }
 }
 
 impl From<ActiveFrame> for InactiveFrame {
@@ -143,6 +157,25 @@ impl <'a> Compiler<'a> {
             .collect()
     }
     
+    /// Returns the given range, unless it is in the standard library; then
+    /// the range of the innermost call from outside the standard library is
+    /// returned instead. For example, a tag created by `@href` is attributed
+    /// to the call site of `@href`.
+    pub(super) fn range_outside_stdlib(&self, range: SourceRange) -> SourceRange {
+        let Some(stdlib_src_id) = self.ctx.module_cache.stdlib_src_id else {
+            return range;
+        };
+        if range.src_id != stdlib_src_id {
+            return range;
+        }
+        self.call_stack.iter()
+            .rev()
+            .filter_map(|frame| frame.call.as_ref())
+            .map(|&(_, call_range)| call_range)
+            .find(|call_range| call_range.src_id != stdlib_src_id)
+            .unwrap_or(range)
+    }
+    
     pub(super) fn get_var(&mut self, name_id: NameID) -> errors::PapyriResult<Value> {
         self.frame()
             .get(name_id, false)
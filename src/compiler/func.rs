@@ -7,6 +7,7 @@ use crate::utils::sourcefile::SourceRange;
 use super::base::Compiler;
 use super::frame::InactiveFrame;
 use super::native::NativeFunc;
+use super::native_custom::CustomNativeFunc;
 use super::signature::{RcFuncSignature, PartialParams};
 use super::value::{Value, Dict};
 
@@ -14,6 +15,7 @@ use super::value::{Value, Dict};
 pub enum Func {
     NonNative(Rc<NonNativeFunc>),
     Native(NativeFunc, NameID, RcFuncSignature),
+    CustomNative(Rc<CustomNativeFunc>),
     Bound(Rc<(Func, PartialParams)>),
 }
 
@@ -22,6 +24,7 @@ impl Func {
         match self {
             Func::NonNative(f) => f.name_id,
             Func::Native(_, name_id, _) => *name_id,
+            Func::CustomNative(f) => f.name_id,
             Func::Bound(f) => f.0.name_id(),
         }
     }
@@ -30,6 +33,7 @@ impl Func {
         match self {
             Func::NonNative(f) => f.signature.clone(),
             Func::Native(.., sig) => sig.clone(),
+            Func::CustomNative(f) => f.signature.clone(),
             Func::Bound(f) => f.0.signature(),
         }
     }
@@ -129,16 +133,22 @@ impl <'a> Compiler<'a> {
     }
     
     pub(super) fn evaluate_func_call_with_bindings(&mut self, func: Func, bindings: Dict, type_hint: &Type, call_range: SourceRange) -> Reported<Value> {
+        self.take_step(call_range)?;
         match func {
             Func::NonNative(ref f) => {
                 let frame = f.closure.new_child_frame(bindings, func.clone(), call_range);
-                self.evaluate_in_frame(frame, |_self| _self.evaluate_node(f.body.as_ref(), type_hint))
+                self.evaluate_call(call_range, |_self| _self.evaluate_in_frame(frame, |_self| _self.evaluate_node(f.body.as_ref(), type_hint)))
             },
             Func::Native(f, ..) => {
                 self.evaluate_native_func(f, bindings, call_range)
                     .and_then(|v| self.coerce(v, type_hint))
                     .map_err(|e| self.report(e, call_range))
             },
+            Func::CustomNative(f) => {
+                self.evaluate_custom_native_func(f.as_ref(), bindings)
+                    .and_then(|v| self.coerce(v, type_hint))
+                    .map_err(|e| self.report(e, call_range))
+            },
             Func::Bound(f) => {
                 self.evaluate_func_call_with_bindings(f.0.clone(), bindings, type_hint, call_range)
             },
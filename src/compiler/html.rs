@@ -1,12 +1,13 @@
 use std::rc::Rc;
 
-use html5ever::parse_document;
+use html5ever::{local_name, namespace_url, ns, parse_document, parse_fragment, Namespace, Prefix, QualName};
+use indexmap::IndexMap;
 use html5ever::tendril::TendrilSink;
 use markup5ever_rcdom::{Handle, NodeData, RcDom};
 
 use crate::errors;
 use crate::errors::RuntimeError;
-use crate::utils::{NameID, StringPool, taginfo, text};
+use crate::utils::{str_ids, NameID, StringPool, taginfo, text};
 
 use super::tag::Tag;
 use super::value::RcStr;
@@ -152,6 +153,16 @@ impl HTML {
         }
     }
 
+    /// Indicates whether this HTML item is or contains a tag with the given
+    /// name.
+    pub(super) fn contains_tag(&self, name_id: NameID) -> bool {
+        match self {
+            HTML::Tag(tag) => tag.name_id == name_id || tag.content.contains_tag(name_id),
+            HTML::Sequence(seq) => seq.iter().any(|child| child.contains_tag(name_id)),
+            _ => false,
+        }
+    }
+    
     pub(super) fn nodes(&self) -> &[HTML] {
         match self {
             HTML::Empty => &[],
@@ -257,6 +268,110 @@ pub(super) fn parse_html(text: &str, pool: &mut StringPool) -> Result<HTML, Runt
     Ok(result)
 }
 
+/// Parses the given string as either a complete HTML document, or a fragment
+/// of HTML, depending on whether it begins with a doctype or `<html>` tag.
+pub(super) fn parse_html_document_or_fragment(text: &str, pool: &mut StringPool) -> Result<HTML, RuntimeError> {
+    let start = text.trim_start()
+        .get(..5)
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if start == "<!doc" || start == "<html" {
+        return parse_html(text, pool);
+    }
+    
+    let context = QualName::new(None, ns!(html), local_name!("body"));
+    let dom = parse_fragment(RcDom::default(), Default::default(), context, Vec::new())
+        .from_utf8()
+        .read_from(&mut text.as_bytes())
+        .unwrap(); // it's an io::Error, I don't *think* it can happen
+    for error in dom.errors {
+        if error != "Duplicate attribute" {
+            return Err(RuntimeError::HtmlParseError(error.to_string()));
+        }
+    }
+    
+    // The fragment parser wraps the content in an `<html>` element.
+    let document = dom.document.children.borrow();
+    let Some(root) = document.first() else {
+        return Ok(HTML::Empty);
+    };
+    let result = HTML::from_iter(
+        root.children.borrow().iter()
+            .filter_map(|node| node_to_html(node, pool))
+    );
+    Ok(result)
+}
+
+/// Parses the given string as XML. Unlike HTML, tag and attribute names are
+/// case-sensitive, and namespace prefixes are kept as part of the names, e.g.
+/// `xlink:href`. Namespace declarations are kept as `xmlns` attributes.
+pub(super) fn parse_xml(text: &str, pool: &mut StringPool) -> Result<HTML, RuntimeError> {
+    let dom = xml5ever::driver::parse_document(RcDom::default(), Default::default())
+        .from_utf8()
+        .read_from(&mut text.as_bytes())
+        .unwrap(); // it's an io::Error, I don't *think* it can happen
+    if let Some(error) = dom.errors.first() {
+        return Err(RuntimeError::XmlParseError(error.to_string()));
+    }
+    
+    let result = HTML::from_iter(
+        dom.document.children.borrow().iter()
+            .filter_map(|node| {
+                let mut prefixes = IndexMap::new();
+                let mut tag = xml_node_to_html(node, pool, &ns!(), &mut prefixes)?;
+                if let HTML::Tag(tag) = &mut tag {
+                    let tag = Rc::make_mut(tag);
+                    for (prefix, ns) in prefixes {
+                        tag.attributes.insert(pool.insert(format!("xmlns:{prefix}")), Some(ns.as_ref().into()));
+                    }
+                }
+                Some(tag)
+            })
+    );
+    Ok(result)
+}
+
+fn xml_name_to_id(name: &QualName, pool: &mut StringPool, prefixes: &mut IndexMap<Prefix, Namespace>) -> NameID {
+    match &name.prefix {
+        Some(prefix) => {
+            prefixes.entry(prefix.clone())
+                .or_insert_with(|| name.ns.clone());
+            pool.insert(format!("{}:{}", prefix.as_ref(), name.local.as_ref()))
+        },
+        None => pool.insert(name.local.as_ref()),
+    }
+}
+
+fn xml_node_to_html(node: &Handle, pool: &mut StringPool, parent_ns: &Namespace, prefixes: &mut IndexMap<Prefix, Namespace>) -> Option<HTML> {
+    match &node.data {
+        NodeData::Element {
+            name,
+            attrs,
+            ..
+        } => {
+            let children = HTML::from_iter(
+                node.children.borrow().iter()
+                    .filter_map(|child| xml_node_to_html(child, pool, &name.ns, prefixes))
+            );
+            
+            let name_id = xml_name_to_id(name, pool, prefixes);
+            let mut new_tag = Tag::new(name_id, children);
+            if name.prefix.is_none() && name.ns != *parent_ns {
+                new_tag.attributes.insert(str_ids::XMLNS, Some(name.ns.as_ref().into()));
+            }
+            
+            for attr in attrs.borrow().iter() {
+                let name = xml_name_to_id(&attr.name, pool, prefixes);
+                new_tag.attributes.insert(name, Some(attr.value.as_ref().into()));
+            }
+            
+            Some(HTML::Tag(new_tag.into()))
+        },
+        NodeData::Text { contents } => Some(contents.borrow().to_string().into()),
+        _ => None,
+    }
+}
+
 fn node_to_html(node: &Handle, pool: &mut StringPool) -> Option<HTML> {
     match &node.data {
         NodeData::Element {
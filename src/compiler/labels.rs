@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use normalize_path::NormalizePath;
+
+use crate::errors;
+use crate::utils::{str_ids, taginfo, NameID};
+use crate::utils::sourcefile::SourceRange;
+use super::base::Compiler;
+use super::context::Context;
+use super::html::HTML;
+use super::tag::Tag;
+use super::value::RcStr;
+
+/// The maximum length of an element ID generated from a label's name.
+const MAX_ID_LENGTH: usize = 64;
+
+/// Returns the kind of label to use for an element with the given tag name,
+/// if it can be inferred.
+fn infer_kind(name_id: NameID) -> Option<&'static str> {
+    match name_id {
+        str_ids::FIGURE => Some("Figure"),
+        str_ids::TABLE => Some("Table"),
+        str_ids::PRE => Some("Listing"),
+        str_ids::MATH => Some("Equation"),
+        str_ids::SECTION => Some("Section"),
+        name_id if taginfo::heading_level(name_id).is_some() => Some("Section"),
+        _ => None,
+    }
+}
+
+/// Returns the only element in the given HTML, ignoring whitespace.
+fn single_element(html: &HTML) -> Option<&Tag> {
+    let mut elements = html.nodes()
+        .iter()
+        .filter(|node| !node.is_whitespace());
+    match (elements.next(), elements.next()) {
+        (Some(HTML::Tag(tag)), None) => Some(tag),
+        _ => None,
+    }
+}
+
+/// Returns a relative URL from one output page to another.
+fn relative_url(from: &Path, to: &Path) -> String {
+    let from_dir: Vec<_> = from.parent()
+        .map_or_else(Vec::new, |p| p.components().collect());
+    let to: Vec<_> = to.components().collect();
+    let common = from_dir.iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    
+    let mut parts = vec!["..".to_string(); from_dir.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()));
+    parts.join("/")
+}
+
+/// Replaces the `@ref` placeholders in the given HTML with links, for those
+/// labels which can be resolved by the given function. The function returns
+/// the URL and text for the link; placeholders for other labels are left
+/// unchanged.
+fn replace_refs(html: &HTML, resolve: &mut impl FnMut(&str) -> Option<(String, RcStr)>) -> HTML {
+    match html {
+        HTML::Tag(tag) if tag.name_id == str_ids::_REF => {
+            let name = tag.get_attr(str_ids::NAME).flatten().unwrap_or("");
+            let Some((url, text)) = resolve(name) else {
+                return html.clone();
+            };
+            let mut link = Tag::new(str_ids::A, HTML::text(text))
+                .str_attr(str_ids::HREF, &url);
+            link.range = tag.range;
+            link.into()
+        },
+        HTML::Tag(tag) if tag.content.contains_tag(str_ids::_REF) => {
+            let mut new_tag = Tag::clone(tag);
+            new_tag.content = replace_refs(&tag.content, resolve);
+            new_tag.into()
+        },
+        HTML::Sequence(seq) => seq.iter()
+            .map(|child| replace_refs(child, resolve))
+            .collect(),
+        _ => html.clone(),
+    }
+}
+
+/// A labelled element which `@ref` can refer to.
+struct Target {
+    id: RcStr,
+    text: RcStr,
+}
+
+/// The state of the numbering of labels and headings in a document.
+struct Numbering {
+    /// The highest heading level which is numbered.
+    base_level: u8,
+    heading_counters: [u32; 7],
+    
+    /// The section numbers of the headings seen so far, in order; headings
+    /// above the base level have no number.
+    heading_numbers: Vec<Option<String>>,
+    
+    /// The number of labels seen so far, of each kind.
+    kind_counters: HashMap<RcStr, u32>,
+    
+    targets: HashMap<RcStr, Target>,
+}
+
+impl Numbering {
+    /// Creates a new numbering state for the given document. Headings are
+    /// numbered from the highest level used in the document; but if there is
+    /// only one heading at that level, it is the document's title, and the
+    /// level below is used instead.
+    fn new(html: &HTML) -> Numbering {
+        fn collect_levels(html: &HTML, levels: &mut Vec<u8>) {
+            match html {
+                HTML::Tag(tag) => match taginfo::heading_level(tag.name_id) {
+                    Some(level) => levels.push(level),
+                    None => collect_levels(&tag.content, levels),
+                },
+                HTML::Sequence(seq) => {
+                    for child in seq.iter() {
+                        collect_levels(child, levels);
+                    }
+                },
+                _ => {},
+            }
+        }
+        let mut levels = Vec::new();
+        collect_levels(html, &mut levels);
+        
+        let min_level = levels.iter().copied().min().unwrap_or(1);
+        let base_level = if levels.iter().filter(|&&l| l == min_level).count() == 1 {
+            levels.iter().copied().filter(|&l| l > min_level).min().unwrap_or(min_level)
+        } else {
+            min_level
+        };
+        
+        Numbering {
+            base_level,
+            heading_counters: [0; 7],
+            heading_numbers: Vec::new(),
+            kind_counters: HashMap::new(),
+            targets: HashMap::new(),
+        }
+    }
+    
+    fn visit_heading(&mut self, level: u8) {
+        let level = level as usize;
+        let base = self.base_level as usize;
+        let number = (level >= base).then(|| {
+            self.heading_counters[level] += 1;
+            self.heading_counters[level + 1..].fill(0);
+            self.heading_counters[base..=level].iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(".")
+        });
+        self.heading_numbers.push(number);
+    }
+    
+    fn next_number(&mut self, kind: &RcStr) -> u32 {
+        let counter = self.kind_counters.entry(kind.clone()).or_default();
+        *counter += 1;
+        *counter
+    }
+}
+
+/// Collects the labels from each page in a build, so that `@ref` can refer to
+/// labels on other pages. References to labels in the same page are resolved
+/// when the page is compiled; other references are left as placeholders,
+/// which are resolved by this struct once the labels from all pages have been
+/// collected.
+pub struct CrossReferences {
+    targets: HashMap<RcStr, (PathBuf, Target)>,
+}
+
+impl Default for CrossReferences {
+    fn default() -> CrossReferences {
+        CrossReferences::new()
+    }
+}
+
+impl CrossReferences {
+    /// Creates a new, empty collection of labels.
+    pub fn new() -> CrossReferences {
+        CrossReferences {targets: HashMap::new()}
+    }
+    
+    /// Indicates whether the given page has references which could not be
+    /// resolved when it was compiled.
+    pub fn has_unresolved(html: &HTML) -> bool {
+        html.contains_tag(str_ids::_REF)
+    }
+    
+    /// Adds the labels in the given output page. If a label is defined on more
+    /// than one page, the first definition is used.
+    pub fn add_page(&mut self, path: &Path, html: &HTML) {
+        match html {
+            HTML::Tag(tag) if tag.name_id == str_ids::_LABEL => {
+                let name = tag.attributes.get(&str_ids::NAME).cloned().flatten();
+                let id = tag.attributes.get(&str_ids::ID).cloned().flatten();
+                let text = tag.attributes.get(&str_ids::TEXT).cloned().flatten();
+                if let (Some(name), Some(id), Some(text)) = (name, id, text) {
+                    self.targets.entry(name)
+                        .or_insert_with(|| (path.normalize(), Target {id, text}));
+                }
+                self.add_page(path, &tag.content);
+            },
+            HTML::Tag(tag) => self.add_page(path, &tag.content),
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.add_page(path, child);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    /// Resolves the remaining references in the given output page, and
+    /// reports a warning in the given context for each reference to a label
+    /// which doesn't exist.
+    pub fn resolve(&self, path: &Path, html: &HTML, ctx: &mut Context) -> HTML {
+        let path = path.normalize();
+        let html = replace_refs(html, &mut |name| {
+            let (target_path, target) = self.targets.get(name)?;
+            let url = if *target_path == path {
+                format!("#{}", target.id)
+            } else {
+                format!("{}#{}", relative_url(&path, target_path), target.id)
+            };
+            Some((url, target.text.clone()))
+        });
+        ctx.report_unknown_labels(&html);
+        html
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Creates a placeholder for a labelled element, which is numbered when
+    /// the document is complete. If no kind is given, it is inferred from the
+    /// element's tag name.
+    pub(super) fn label_impl(&mut self, name: RcStr, kind: Option<RcStr>, content: HTML, call_range: SourceRange) -> errors::PapyriResult<Tag> {
+        let Some(element) = single_element(&content) else {
+            let e = errors::RuntimeError::LabelNotElement;
+            return Err(e.into());
+        };
+        let kind = match kind {
+            Some(kind) => kind,
+            None => infer_kind(element.name_id)
+                .ok_or_else(|| errors::RuntimeError::LabelKindUnknown(self.get_name(element.name_id)))?
+                .into(),
+        };
+        
+        let mut tag = Tag::new(str_ids::_LABEL, content)
+            .str_attr(str_ids::NAME, &name)
+            .str_attr(str_ids::KIND, &kind);
+        tag.range = Some(call_range);
+        Ok(tag)
+    }
+}
+
+impl Context {
+    /// Numbers the labelled elements in the given document, and replaces the
+    /// `@ref` placeholders which refer to them with links. Labelled elements
+    /// are given IDs if they don't already have them, and the captions of
+    /// labelled figures and tables are prefixed with their numbers, such as
+    /// "Figure 3: ". References to labels which are not in the document are
+    /// left unchanged, since they may refer to other pages.
+    ///
+    /// If there are no labels or references, the document is returned
+    /// unchanged.
+    pub(super) fn resolve_labels(&mut self, html: HTML) -> HTML {
+        if !html.contains_tag(str_ids::_LABEL) && !html.contains_tag(str_ids::_REF) {
+            return html;
+        }
+        
+        self.reserve_ids(&html);
+        let mut numbering = Numbering::new(&html);
+        let html = self.number_labels(&html, &mut numbering);
+        replace_refs(&html, &mut |name| {
+            let target = numbering.targets.get(name)?;
+            Some((format!("#{}", target.id), target.text.clone()))
+        })
+    }
+    
+    fn number_labels(&mut self, html: &HTML, numbering: &mut Numbering) -> HTML {
+        match html {
+            HTML::Tag(tag) if tag.name_id == str_ids::_LABEL && tag.attributes.contains_key(&str_ids::KIND) => {
+                let first_heading = numbering.heading_numbers.len();
+                let content = self.number_labels(&tag.content, numbering);
+                let Some(element) = single_element(&content) else {
+                    errors::ice("Label content is not a single element");
+                };
+                
+                let name: RcStr = tag.get_attr(str_ids::NAME).flatten().unwrap_or("").into();
+                let kind: RcStr = tag.get_attr(str_ids::KIND).flatten().unwrap_or("").into();
+                let is_section = element.name_id == str_ids::SECTION || taginfo::heading_level(element.name_id).is_some();
+                let number = if is_section {
+                    numbering.heading_numbers.get(first_heading).cloned().flatten()
+                } else {
+                    Some(numbering.next_number(&kind).to_string())
+                };
+                let text: RcStr = match number {
+                    Some(number) => format!("{kind} {number}").into(),
+                    None => {
+                        // an unnumbered heading, such as the document title,
+                        // is referred to by its text
+                        let mut text = Vec::new();
+                        self.render(&element.content, false, &mut text)
+                            .unwrap_or_else(|_| errors::ice("Failed to render label text"));
+                        String::from_utf8_lossy(&text).trim().into()
+                    },
+                };
+                
+                let mut element = Tag::clone(element);
+                let id: RcStr = match element.get_attr(str_ids::ID).flatten() {
+                    Some(id) => id.into(),
+                    None => {
+                        let id = self.unique_ids.get_unique_id(&name, MAX_ID_LENGTH);
+                        element.attributes.insert(str_ids::ID, Some(id.clone()));
+                        id
+                    },
+                };
+                let caption_name = match element.name_id {
+                    str_ids::FIGURE => Some(str_ids::FIGCAPTION),
+                    str_ids::TABLE => Some(str_ids::CAPTION),
+                    _ => None,
+                };
+                if let Some(caption_name) = caption_name {
+                    let prefix = HTML::text(format!("{text}: "));
+                    element.content = element.content.nodes()
+                        .iter()
+                        .map(|node| match node {
+                            HTML::Tag(caption) if caption.name_id == caption_name => {
+                                let mut caption = Tag::clone(caption);
+                                caption.content = [prefix.clone(), caption.content].into_iter().collect();
+                                caption.into()
+                            },
+                            _ => node.clone(),
+                        })
+                        .collect();
+                }
+                
+                if numbering.targets.contains_key(&name) {
+                    if let Some(range) = tag.range {
+                        let src = self.source_files.get(range.src_id);
+                        self.diagnostics.report_static(errors::Warning::DuplicateLabel(name.clone()), src, range);
+                    }
+                } else {
+                    numbering.targets.insert(name.clone(), Target {id: id.clone(), text: text.clone()});
+                }
+                
+                let mut new_tag = Tag::new(str_ids::_LABEL, element.into())
+                    .str_attr(str_ids::NAME, &name)
+                    .str_attr(str_ids::ID, &id)
+                    .str_attr(str_ids::TEXT, &text);
+                new_tag.range = tag.range;
+                new_tag.into()
+            },
+            HTML::Tag(tag) => {
+                if let Some(level) = taginfo::heading_level(tag.name_id) {
+                    numbering.visit_heading(level);
+                }
+                let mut new_tag = Tag::clone(tag);
+                new_tag.content = self.number_labels(&tag.content, numbering);
+                new_tag.into()
+            },
+            HTML::Sequence(seq) => seq.iter()
+                .map(|child| self.number_labels(child, numbering))
+                .collect(),
+            _ => html.clone(),
+        }
+    }
+    
+    /// Reports a warning for each `@ref` placeholder in the given HTML, since
+    /// it refers to a label which doesn't exist.
+    pub(super) fn report_unknown_labels(&mut self, html: &HTML) {
+        match html {
+            HTML::Tag(tag) if tag.name_id == str_ids::_REF => {
+                let name = tag.get_attr(str_ids::NAME).flatten().unwrap_or("");
+                if let Some(range) = tag.range {
+                    let src = self.source_files.get(range.src_id);
+                    self.diagnostics.report_static(errors::Warning::UnknownLabel(name.into()), src, range);
+                }
+            },
+            HTML::Tag(tag) => self.report_unknown_labels(&tag.content),
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.report_unknown_labels(child);
+                }
+            },
+            _ => {},
+        }
+    }
+}
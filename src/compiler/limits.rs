@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+use crate::errors;
+use crate::utils::sourcefile::SourceRange;
+use super::base::Compiler;
+
+/// The number of evaluation steps between checks of the time limit, since
+/// reading the clock is much slower than evaluating a simple expression. This
+/// must be a power of two.
+const STEPS_PER_CLOCK_CHECK: u64 = 1024;
+
+/// If less than this much native stack remains when a function is called, the
+/// call is evaluated on a new stack segment. This must be more than the stack
+/// used between two nested calls, which is a few kilobytes in debug builds.
+const STACK_RED_ZONE: usize = 128 * 1024;
+
+/// The size of each new stack segment allocated for deep recursion.
+const STACK_SEGMENT_SIZE: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+/// Limits on the work which compiling a Papyri source file may do. When a
+/// limit is exceeded, compilation is aborted with a runtime error.
+pub struct Limits {
+    /// The maximum depth of nested calls to Papyri functions, which stops
+    /// runaway recursion. The native stack is extended as needed, but each
+    /// nested call uses about 3 KiB of memory in release builds, or about
+    /// 6 KiB in debug builds.
+    pub max_call_depth: usize,
+    
+    /// The maximum number of evaluation steps in one compilation job. Each
+    /// evaluated expression and each function call counts as one step. There
+    /// is no limit if this is `None`.
+    pub max_steps: Option<u64>,
+    
+    /// The maximum wall-clock time for one compilation job. There is no limit
+    /// if this is `None`.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_call_depth: 5000,
+            max_steps: None,
+            timeout: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// Tracks the work done in the current compilation job. A job begins when a
+/// source file is compiled, and includes any modules it imports.
+pub(super) struct Budget {
+    active_jobs: u32,
+    steps: u64,
+    call_depth: usize,
+    deadline: Option<Instant>,
+    exhausted: bool,
+}
+
+impl Budget {
+    pub(super) fn begin(&mut self, limits: &Limits) {
+        if self.active_jobs == 0 {
+            self.steps = 0;
+            self.deadline = limits.timeout.map(|t| Instant::now() + t);
+            self.exhausted = false;
+        }
+        self.active_jobs += 1;
+    }
+    
+    pub(super) fn end(&mut self) {
+        self.active_jobs -= 1;
+    }
+    
+    /// Counts one evaluation step. Returns `Err(Some(e))` if a limit has just
+    /// been exceeded, or `Err(None)` if one was already exceeded.
+    fn step(&mut self, limits: &Limits) -> Result<(), Option<errors::RuntimeError>> {
+        if self.exhausted {
+            return Err(None);
+        }
+        
+        self.steps += 1;
+        let e = if matches!(limits.max_steps, Some(max) if self.steps > max) {
+            errors::RuntimeError::StepLimitExceeded(self.steps - 1)
+        } else if self.steps & (STEPS_PER_CLOCK_CHECK - 1) == 0 && matches!(self.deadline, Some(deadline) if Instant::now() >= deadline) {
+            errors::RuntimeError::TimeLimitExceeded(limits.timeout.unwrap_or_default())
+        } else {
+            return Ok(());
+        };
+        self.exhausted = true;
+        Err(Some(e))
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Evaluates a call to a non-native function, if the call depth limit
+    /// allows it. If the native stack is close to running out, the call is
+    /// evaluated on a newly-allocated stack segment, so that the depth of
+    /// recursion is limited only by `max_call_depth`.
+    pub(super) fn evaluate_call<T>(&mut self, range: SourceRange, f: impl FnOnce(&mut Compiler) -> errors::Reported<T>) -> errors::Reported<T> {
+        let max_depth = self.ctx.limits.max_call_depth;
+        if self.ctx.budget.call_depth >= max_depth {
+            let e = errors::RuntimeError::CallDepthExceeded(max_depth);
+            return Err(self.report(e, range));
+        }
+        
+        self.ctx.budget.call_depth += 1;
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT_SIZE, || f(self));
+        self.ctx.budget.call_depth -= 1;
+        result
+    }
+    
+    /// Counts one evaluation step against this compiler's budget. If a limit
+    /// is exceeded, an error is reported once; evaluation is then aborted
+    /// without reporting further errors.
+    pub(super) fn take_step(&mut self, range: SourceRange) -> errors::Reported {
+        match self.ctx.budget.step(&self.ctx.limits) {
+            Ok(()) => Ok(()),
+            Err(Some(e)) => Err(self.report(e, range)),
+            Err(None) => Err(errors::AlreadyReported),
+        }
+    }
+}
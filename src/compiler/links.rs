@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use normalize_path::NormalizePath;
+
+use crate::errors;
+use crate::utils::str_ids;
+use crate::utils::sourcefile::SourceRange;
+use super::context::Context;
+use super::html::HTML;
+use super::value::RcStr;
+
+/// Indicates whether the given URL refers to another site, or uses a scheme
+/// such as `mailto:`, rather than being a relative URL.
+fn is_external_url(url: &str) -> bool {
+    if url.starts_with("//") {
+        return true;
+    }
+    let scheme_end = url.find([':', '/', '?', '#']);
+    matches!(scheme_end, Some(i) if url[i..].starts_with(':'))
+}
+
+/// Decodes `%XX` escapes in a URL path or fragment. Invalid escapes are left
+/// as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = s.get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(b) = escaped {
+            out.push(b);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// A link from an output page, to be checked by the `LinkChecker`.
+struct Link {
+    url: RcStr,
+    range: SourceRange,
+}
+
+#[derive(Default)]
+/// The element IDs and links found in an output page.
+struct Page {
+    ids: HashSet<String>,
+    links: Vec<Link>,
+}
+
+impl Page {
+    fn collect(&mut self, html: &HTML) {
+        match html {
+            HTML::Tag(tag) => {
+                if let Some(Some(id)) = tag.get_attr(str_ids::ID) {
+                    self.ids.insert(id.to_string());
+                }
+                if let (str_ids::A, Some(Some(name))) = (tag.name_id, tag.get_attr(str_ids::NAME)) {
+                    self.ids.insert(name.to_string());
+                }
+                
+                let url_attr = match tag.name_id {
+                    str_ids::A | str_ids::LINK => Some(str_ids::HREF),
+                    str_ids::IMG | str_ids::SCRIPT => Some(str_ids::SRC),
+                    _ => None,
+                };
+                let url = url_attr.and_then(|k| tag.attributes.get(&k))
+                    .and_then(Option::as_ref);
+                if let (Some(url), Some(range)) = (url, tag.range) {
+                    self.links.push(Link {url: url.clone(), range});
+                }
+                
+                self.collect(&tag.content);
+            },
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.collect(child);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Checks that links between output pages refer to files which exist, and
+/// that their fragments refer to elements which exist on the target page.
+/// Pages are added as they are built, and then checked once all of the files
+/// have been written to the output directory.
+///
+/// Only links in `<a href>`, `<img src>`, `<link href>` and `<script src>`
+/// tags are checked, and only where the tag was written in Papyri source;
+/// links in HTML from other sources, such as `@fetch::html`, are not checked.
+/// Absolute URLs are not checked.
+pub struct LinkChecker {
+    out_dir: Option<PathBuf>,
+    pages: BTreeMap<PathBuf, Page>,
+}
+
+impl LinkChecker {
+    /// Creates a new link checker. Links beginning with `/` are resolved
+    /// relative to the output directory; if there is none, such links are not
+    /// checked.
+    pub fn new(out_dir: Option<&Path>) -> LinkChecker {
+        LinkChecker {
+            out_dir: out_dir.map(Path::to_path_buf),
+            pages: BTreeMap::new(),
+        }
+    }
+    
+    /// Adds an output page to this link checker. Links in the page are
+    /// resolved relative to the page's path.
+    pub fn add_page(&mut self, path: &Path, html: &HTML) {
+        let mut page = Page::default();
+        page.collect(html);
+        self.pages.insert(path.normalize(), page);
+    }
+    
+    /// Resolves a URL from the given page, returning the path of the target
+    /// file and the URL's fragment, if it has one. Returns `None` if the link
+    /// should not be checked.
+    fn resolve(&self, page_path: &Path, url: &str) -> Option<(PathBuf, Option<String>)> {
+        if is_external_url(url) {
+            return None;
+        }
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url, Some(percent_decode(fragment))),
+            None => (url, None),
+        };
+        let url = url.split('?')
+            .next()
+            .unwrap_or(url);
+        
+        let mut path = if url.is_empty() {
+            return Some((page_path.to_path_buf(), fragment));
+        } else if let Some(url) = url.strip_prefix('/') {
+            self.out_dir.as_ref()?.join(percent_decode(url))
+        } else {
+            page_path.parent()
+                .unwrap_or(Path::new(""))
+                .join(percent_decode(url))
+        };
+        if url.ends_with('/') {
+            path.push("index.html");
+        }
+        Some((path.normalize(), fragment))
+    }
+    
+    /// Checks the links in all of the pages added to this link checker, and
+    /// reports a warning in the given context for each broken link.
+    pub fn check(&self, ctx: &mut Context) {
+        for (page_path, page) in self.pages.iter() {
+            for link in page.links.iter() {
+                let Some((target_path, fragment)) = self.resolve(page_path, &link.url) else {
+                    continue;
+                };
+                
+                let target = self.pages.get(&target_path);
+                let e = if target.is_none() && ctx.file_system.canonicalize(&target_path).is_err() {
+                    errors::Warning::BrokenLink(link.url.clone())
+                } else if matches!((target, fragment), (Some(t), Some(f)) if !f.is_empty() && f != "top" && !t.ids.contains(&f)) {
+                    errors::Warning::BrokenLinkFragment(link.url.clone())
+                } else {
+                    continue;
+                };
+                let src = ctx.source_files.get(link.range.src_id);
+                ctx.diagnostics.report_static(e, src, link.range);
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::utils::{str_ids, NameID, StringPool, taginfo};
+use super::html::HTML;
+use super::tag::Tag;
+
+/// Renders HTML content as Markdown. Tags which have no Markdown equivalent
+/// are rendered as their contents, and block-level content is separated by
+/// blank lines.
+pub(super) fn render_markdown(html: &HTML, string_pool: &StringPool) -> String {
+    let mut renderer = MarkdownRenderer {string_pool, verbatim: false};
+    let out = renderer.render(html);
+    
+    // Block-level content is surrounded by blank lines, which need to be
+    // collapsed.
+    let mut s = String::new();
+    for block in out.split("\n\n").map(|b| b.trim_matches('\n')).filter(|b| !b.trim().is_empty()) {
+        if !s.is_empty() {
+            s += "\n\n";
+        }
+        s += block;
+    }
+    if !s.is_empty() {
+        s.push('\n');
+    }
+    s
+}
+
+/// Escapes characters which have special meanings in Markdown text.
+fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Prefixes each line of `s` with `first` for the first line, or `rest` for
+/// subsequent lines. Blank lines are not indented.
+fn prefix_lines(s: &str, first: &str, rest: &str) -> String {
+    let mut out = String::new();
+    for (i, line) in s.lines().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let prefix = if i == 0 { first } else { rest };
+        if line.is_empty() {
+            out += prefix.trim_end();
+        } else {
+            out += prefix;
+            out += line;
+        }
+    }
+    out
+}
+
+fn block(s: &str) -> String {
+    format!("\n\n{}\n\n", s.trim_matches('\n'))
+}
+
+struct MarkdownRenderer<'a> {
+    string_pool: &'a StringPool,
+    
+    /// Whether text is currently being rendered without escaping, i.e. inside
+    /// a code span or code block.
+    verbatim: bool,
+}
+
+impl <'a> MarkdownRenderer<'a> {
+    fn render(&mut self, html: &HTML) -> String {
+        match html {
+            HTML::Tag(tag) => self.render_tag(tag),
+            HTML::Sequence(seq) => seq.iter()
+                .map(|child| self.render(child))
+                .collect(),
+            HTML::Text(t) => if self.verbatim { t.to_string() } else { escape_markdown(t) },
+            HTML::Whitespace => " ".to_string(),
+            HTML::RawNewline => if self.verbatim { "\n" } else { " " }.to_string(),
+            HTML::Empty => String::new(),
+        }
+    }
+    
+    fn render_verbatim(&mut self, html: &HTML) -> String {
+        let old = std::mem::replace(&mut self.verbatim, true);
+        let s = self.render(html);
+        self.verbatim = old;
+        s
+    }
+    
+    fn attr(&self, tag: &Tag, name_id: NameID) -> String {
+        tag.get_attr(name_id)
+            .flatten()
+            .unwrap_or("")
+            .to_string()
+    }
+    
+    fn render_tag(&mut self, tag: &Tag) -> String {
+        match tag.name_id {
+            str_ids::H1 | str_ids::H2 | str_ids::H3 | str_ids::H4 | str_ids::H5 | str_ids::H6 => {
+                let level = match tag.name_id {
+                    str_ids::H1 => 1,
+                    str_ids::H2 => 2,
+                    str_ids::H3 => 3,
+                    str_ids::H4 => 4,
+                    str_ids::H5 => 5,
+                    _ => 6,
+                };
+                let content = self.render(&tag.content);
+                block(&format!("{} {}", "#".repeat(level), content.trim()))
+            },
+            str_ids::B | str_ids::STRONG => format!("**{}**", self.render(&tag.content)),
+            str_ids::I | str_ids::EM => format!("*{}*", self.render(&tag.content)),
+            str_ids::CODE if self.verbatim => self.render(&tag.content),
+            str_ids::CODE => format!("`{}`", self.render_verbatim(&tag.content)),
+            str_ids::PRE => {
+                let content = self.render_verbatim(&tag.content);
+                block(&format!("```\n{}\n```", content.trim_end_matches('\n')))
+            },
+            str_ids::A => {
+                let content = self.render(&tag.content);
+                match tag.get_attr(str_ids::HREF).flatten() {
+                    Some(href) => format!("[{content}]({href})"),
+                    None => content,
+                }
+            },
+            str_ids::IMG => format!("![{}]({})", escape_markdown(&self.attr(tag, str_ids::ALT)), self.attr(tag, str_ids::SRC)),
+            str_ids::MATH if tag.get_attr(str_ids::ALTTEXT).flatten().is_some() => {
+                let tex = self.attr(tag, str_ids::ALTTEXT);
+                if tag.get_attr(str_ids::DISPLAY).flatten() == Some("block") {
+                    block(&format!("$$\n{tex}\n$$"))
+                } else {
+                    format!("${tex}$")
+                }
+            },
+            str_ids::BR => "  \n".to_string(),
+            str_ids::HR => block("---"),
+            str_ids::BLOCKQUOTE => {
+                let content = render_markdown(&tag.content, self.string_pool);
+                block(&prefix_lines(content.trim_end(), "> ", "> "))
+            },
+            str_ids::UL | str_ids::OL => {
+                let mut items = Vec::new();
+                for item in tag.content.nodes().iter().filter(|item| !item.is_whitespace()) {
+                    let marker = if tag.name_id == str_ids::OL { format!("{}. ", items.len() + 1) } else { "- ".to_string() };
+                    let indent = " ".repeat(marker.len());
+                    let content = match item {
+                        HTML::Tag(li) if li.name_id == str_ids::LI => render_markdown(&li.content, self.string_pool),
+                        _ => render_markdown(item, self.string_pool),
+                    };
+                    items.push(prefix_lines(content.trim_end(), &marker, &indent));
+                }
+                block(&items.join("\n"))
+            },
+            str_ids::_DOCTYPE | str_ids::HEAD | str_ids::SCRIPT | str_ids::STYLE => String::new(),
+            name_id if taginfo::is_block(name_id) => {
+                let content = render_markdown(&tag.content, self.string_pool);
+                block(&content)
+            },
+            _ => self.render(&tag.content),
+        }
+    }
+}
@@ -0,0 +1,679 @@
+use crate::errors;
+use crate::utils::{str_ids, NameID};
+use super::html::HTML;
+use super::tag::Tag;
+
+/// Returns the character for a Greek letter command, such as `\alpha`.
+fn greek_letter(name: &str) -> Option<&'static str> {
+    let s = match name {
+        "alpha" => "\u{3b1}",
+        "beta" => "\u{3b2}",
+        "gamma" => "\u{3b3}",
+        "delta" => "\u{3b4}",
+        "epsilon" => "\u{3f5}",
+        "varepsilon" => "\u{3b5}",
+        "zeta" => "\u{3b6}",
+        "eta" => "\u{3b7}",
+        "theta" => "\u{3b8}",
+        "vartheta" => "\u{3d1}",
+        "iota" => "\u{3b9}",
+        "kappa" => "\u{3ba}",
+        "lambda" => "\u{3bb}",
+        "mu" => "\u{3bc}",
+        "nu" => "\u{3bd}",
+        "xi" => "\u{3be}",
+        "pi" => "\u{3c0}",
+        "varpi" => "\u{3d6}",
+        "rho" => "\u{3c1}",
+        "varrho" => "\u{3f1}",
+        "sigma" => "\u{3c3}",
+        "varsigma" => "\u{3c2}",
+        "tau" => "\u{3c4}",
+        "upsilon" => "\u{3c5}",
+        "phi" => "\u{3d5}",
+        "varphi" => "\u{3c6}",
+        "chi" => "\u{3c7}",
+        "psi" => "\u{3c8}",
+        "omega" => "\u{3c9}",
+        "Gamma" => "\u{393}",
+        "Delta" => "\u{394}",
+        "Theta" => "\u{398}",
+        "Lambda" => "\u{39b}",
+        "Xi" => "\u{39e}",
+        "Pi" => "\u{3a0}",
+        "Sigma" => "\u{3a3}",
+        "Upsilon" => "\u{3a5}",
+        "Phi" => "\u{3a6}",
+        "Psi" => "\u{3a8}",
+        "Omega" => "\u{3a9}",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Returns the character for a command which is rendered as an identifier,
+/// such as `\infty`.
+fn identifier_symbol(name: &str) -> Option<&'static str> {
+    let s = match name {
+        "infty" => "\u{221e}",
+        "partial" => "\u{2202}",
+        "nabla" => "\u{2207}",
+        "emptyset" | "varnothing" => "\u{2205}",
+        "hbar" => "\u{210f}",
+        "ell" => "\u{2113}",
+        "aleph" => "\u{2135}",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Returns the character for a command which is rendered as an operator,
+/// such as `\times`.
+fn operator_symbol(name: &str) -> Option<&'static str> {
+    let s = match name {
+        "pm" => "\u{b1}",
+        "mp" => "\u{2213}",
+        "times" => "\u{d7}",
+        "div" => "\u{f7}",
+        "cdot" => "\u{22c5}",
+        "ast" => "\u{2217}",
+        "circ" => "\u{2218}",
+        "le" | "leq" => "\u{2264}",
+        "ge" | "geq" => "\u{2265}",
+        "ne" | "neq" => "\u{2260}",
+        "ll" => "\u{226a}",
+        "gg" => "\u{226b}",
+        "approx" => "\u{2248}",
+        "equiv" => "\u{2261}",
+        "sim" => "\u{223c}",
+        "simeq" => "\u{2243}",
+        "cong" => "\u{2245}",
+        "propto" => "\u{221d}",
+        "in" => "\u{2208}",
+        "notin" => "\u{2209}",
+        "ni" => "\u{220b}",
+        "subset" => "\u{2282}",
+        "subseteq" => "\u{2286}",
+        "supset" => "\u{2283}",
+        "supseteq" => "\u{2287}",
+        "cup" => "\u{222a}",
+        "cap" => "\u{2229}",
+        "setminus" => "\u{2216}",
+        "to" | "rightarrow" => "\u{2192}",
+        "leftarrow" | "gets" => "\u{2190}",
+        "leftrightarrow" => "\u{2194}",
+        "Rightarrow" | "implies" => "\u{21d2}",
+        "Leftarrow" => "\u{21d0}",
+        "Leftrightarrow" | "iff" => "\u{21d4}",
+        "mapsto" => "\u{21a6}",
+        "forall" => "\u{2200}",
+        "exists" => "\u{2203}",
+        "neg" | "lnot" => "\u{ac}",
+        "land" | "wedge" => "\u{2227}",
+        "lor" | "vee" => "\u{2228}",
+        "oplus" => "\u{2295}",
+        "otimes" => "\u{2297}",
+        "perp" => "\u{22a5}",
+        "parallel" => "\u{2225}",
+        "mid" => "\u{2223}",
+        "ldots" | "dots" => "\u{2026}",
+        "cdots" => "\u{22ef}",
+        "vdots" => "\u{22ee}",
+        "ddots" => "\u{22f1}",
+        "langle" => "\u{27e8}",
+        "rangle" => "\u{27e9}",
+        "lfloor" => "\u{230a}",
+        "rfloor" => "\u{230b}",
+        "lceil" => "\u{2308}",
+        "rceil" => "\u{2309}",
+        "|" => "\u{2016}",
+        "{" => "{",
+        "}" => "}",
+        "sum" => "\u{2211}",
+        "prod" => "\u{220f}",
+        "coprod" => "\u{2210}",
+        "int" => "\u{222b}",
+        "iint" => "\u{222c}",
+        "oint" => "\u{222e}",
+        "bigcup" => "\u{22c3}",
+        "bigcap" => "\u{22c2}",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Indicates whether the given command is a function name which is rendered
+/// upright, such as `\sin`.
+fn is_function_name(name: &str) -> bool {
+    matches!(
+        name,
+        "sin" | "cos" | "tan" | "sec" | "csc" | "cot" |
+        "arcsin" | "arccos" | "arctan" | "sinh" | "cosh" | "tanh" |
+        "log" | "ln" | "lg" | "exp" | "det" | "dim" | "ker" | "deg" | "gcd" |
+        "lim" | "max" | "min" | "sup" | "inf" | "arg" | "Pr"
+    )
+}
+
+/// Indicates whether the given operator has its scripts placed above and
+/// below it in display mode, such as `\sum`.
+fn has_limits(op: &str) -> bool {
+    matches!(
+        op,
+        "\u{2211}" | "\u{220f}" | "\u{2210}" | "\u{22c3}" | "\u{22c2}" |
+        "lim" | "max" | "min" | "sup" | "inf" | "det" | "gcd" | "Pr"
+    )
+}
+
+/// Returns the accent character for an accent command, such as `\hat`, and
+/// whether the accent goes below the base.
+fn accent(name: &str) -> Option<(&'static str, bool)> {
+    let a = match name {
+        "hat" | "widehat" => ("^", false),
+        "bar" | "overline" => ("\u{af}", false),
+        "vec" => ("\u{2192}", false),
+        "dot" => ("\u{2d9}", false),
+        "ddot" => ("\u{a8}", false),
+        "tilde" | "widetilde" => ("~", false),
+        "underline" => ("_", true),
+        _ => return None,
+    };
+    Some(a)
+}
+
+/// Returns the width of a spacing command, such as `\quad`.
+fn space_width(name: &str) -> Option<&'static str> {
+    let w = match name {
+        "," => "0.167em",
+        ":" | ">" => "0.222em",
+        ";" => "0.278em",
+        " " => "0.25em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        "!" => "-0.167em",
+        _ => return None,
+    };
+    Some(w)
+}
+
+fn leaf(name_id: NameID, text: &str) -> HTML {
+    HTML::tag(name_id, HTML::text(text))
+}
+
+/// Wraps the given items in an `<mrow>`, unless there is exactly one.
+fn mrow(mut items: Vec<HTML>) -> HTML {
+    if items.len() == 1 {
+        items.pop().unwrap()
+    } else {
+        HTML::tag(str_ids::MROW, items.into_iter().collect())
+    }
+}
+
+/// Returns the text of an `<mo>` or `<mi>` element, if the given HTML is one.
+fn token_text(html: &HTML) -> Option<&str> {
+    match html {
+        HTML::Tag(tag) if matches!(tag.name_id, str_ids::MO | str_ids::MI) => match &tag.content {
+            HTML::Text(t) => Some(t.as_ref()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Sets the `mathvariant` attribute of the identifiers in the given MathML.
+fn set_variant(html: HTML, variant: &str) -> HTML {
+    match html {
+        HTML::Tag(tag) if tag.name_id == str_ids::MI => {
+            let mut tag = Tag::clone(&tag);
+            tag.attributes.insert(str_ids::MATHVARIANT, Some(variant.into()));
+            tag.into()
+        },
+        HTML::Tag(tag) => {
+            let mut new_tag = Tag::clone(&tag);
+            new_tag.content = set_variant(tag.content.clone(), variant);
+            new_tag.into()
+        },
+        HTML::Sequence(seq) => seq.iter()
+            .map(|child| set_variant(child.clone(), variant))
+            .collect(),
+        html => html,
+    }
+}
+
+/// Where a row of math content ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowEnd {
+    /// The end of the source, `}`, `\right` or `\end`.
+    Close,
+    
+    /// `&`, in an environment.
+    Cell,
+    
+    /// `\\`, in an environment.
+    Row,
+}
+
+/// A parser for a subset of TeX math, which produces MathML.
+struct MathParser<'a> {
+    src: &'a str,
+    pos: usize,
+    display: bool,
+}
+
+impl <'a> MathParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+    
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(d) if d == c => {
+                self.pos += 1;
+                Ok(())
+            },
+            Some(d) => Err(format!("expected '{c}', was '{d}'")),
+            None => Err(format!("expected '{c}', was end of source")),
+        }
+    }
+    
+    /// Returns the name of the command at the current position, without
+    /// consuming it. A command is either a backslash followed by letters, or
+    /// a backslash followed by one other character.
+    fn peek_command(&self) -> Option<&'a str> {
+        let rest = self.src[self.pos..].strip_prefix('\\')?;
+        let len = rest.find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let len = if len > 0 { len } else { rest.chars().next()?.len_utf8() };
+        Some(&rest[..len])
+    }
+    
+    fn command(&mut self) -> Option<&'a str> {
+        let name = self.peek_command()?;
+        self.pos += 1 + name.len();
+        Some(name)
+    }
+    
+    /// Reads the text of a `{...}` argument, with balanced braces.
+    fn text_arg(&mut self) -> Result<&'a str, String> {
+        self.expect('{')?;
+        let start = self.pos;
+        let mut depth = 0;
+        for (i, c) in self.src[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    self.pos = start + i + 1;
+                    return Ok(&self.src[start..start + i]);
+                },
+                '}' => depth -= 1,
+                _ => {},
+            }
+        }
+        Err("unmatched '{'".to_string())
+    }
+    
+    /// Reads the text of a `[...]` argument. Only brackets outside of braces
+    /// are matched, so the argument may contain other bracketed arguments,
+    /// and escaped characters are skipped.
+    fn optional_text_arg(&mut self) -> Result<&'a str, String> {
+        self.expect('[')?;
+        let start = self.pos;
+        let mut brace_depth = 0;
+        let mut bracket_depth = 0;
+        let mut escaped = false;
+        for (i, c) in self.src[start..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '{' => brace_depth += 1,
+                '}' if brace_depth > 0 => brace_depth -= 1,
+                '[' if brace_depth == 0 => bracket_depth += 1,
+                ']' if brace_depth == 0 && bracket_depth == 0 => {
+                    self.pos = start + i + 1;
+                    return Ok(&self.src[start..start + i]);
+                },
+                ']' if brace_depth == 0 => bracket_depth -= 1,
+                _ => {},
+            }
+        }
+        Err("unmatched '['".to_string())
+    }
+    
+    /// Parses a row of math content, returning the items in the row and how
+    /// the row ended. `&` and `\\` are only allowed in an environment.
+    fn row(&mut self, in_env: bool) -> Result<(Vec<HTML>, RowEnd), String> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let end = match (self.peek(), self.peek_command()) {
+                (None | Some('}'), _) |
+                (_, Some("right" | "end")) => Some(RowEnd::Close),
+                (Some('&'), _) => Some(RowEnd::Cell),
+                (_, Some("\\")) => Some(RowEnd::Row),
+                _ => None,
+            };
+            match end {
+                Some(RowEnd::Close) => return Ok((items, RowEnd::Close)),
+                Some(end) if in_env => {
+                    self.pos += if end == RowEnd::Cell { 1 } else { 2 };
+                    return Ok((items, end));
+                },
+                Some(RowEnd::Cell) => return Err("'&' is only allowed in an environment".to_string()),
+                Some(_) => return Err("'\\\\' is only allowed in an environment".to_string()),
+                None => {},
+            }
+            
+            let base = if matches!(self.peek(), Some('^' | '_')) {
+                HTML::tag(str_ids::MROW, HTML::Empty)
+            } else {
+                self.atom()?
+            };
+            let item = self.scripts(base)?;
+            items.push(item);
+        }
+    }
+    
+    /// Parses a `{...}` group, returning its content.
+    fn group(&mut self) -> Result<HTML, String> {
+        self.expect('{')?;
+        let (items, _) = self.row(false)?;
+        if self.peek_command().is_some() {
+            let name = self.command().unwrap_or_default();
+            return Err(format!("unexpected '\\{name}'"));
+        }
+        self.expect('}')?;
+        Ok(mrow(items))
+    }
+    
+    /// Parses the argument of a command such as `\frac`; this is either a
+    /// group, or a single character.
+    fn arg(&mut self) -> Result<HTML, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.group(),
+            Some(c) if c.is_ascii_digit() => {
+                self.pos += 1;
+                Ok(leaf(str_ids::MN, &c.to_string()))
+            },
+            Some(_) => self.atom(),
+            None => Err("expected argument, was end of source".to_string()),
+        }
+    }
+    
+    /// Parses a subscript and/or superscript following the given base.
+    fn scripts(&mut self, base: HTML) -> Result<HTML, String> {
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            self.skip_whitespace();
+            let slot = match self.peek() {
+                Some('_') => &mut sub,
+                Some('^') => &mut sup,
+                _ => break,
+            };
+            if slot.is_some() {
+                return Err("double subscript or superscript".to_string());
+            }
+            self.pos += 1;
+            *slot = Some(self.arg()?);
+        }
+        
+        let limits = self.display && token_text(&base).is_some_and(has_limits);
+        let (name_id, scripts) = match (sub, sup, limits) {
+            (None, None, _) => return Ok(base),
+            (Some(sub), None, false) => (str_ids::MSUB, vec![sub]),
+            (None, Some(sup), false) => (str_ids::MSUP, vec![sup]),
+            (Some(sub), Some(sup), false) => (str_ids::MSUBSUP, vec![sub, sup]),
+            (Some(sub), None, true) => (str_ids::MUNDER, vec![sub]),
+            (None, Some(sup), true) => (str_ids::MOVER, vec![sup]),
+            (Some(sub), Some(sup), true) => (str_ids::MUNDEROVER, vec![sub, sup]),
+        };
+        let content = std::iter::once(base)
+            .chain(scripts)
+            .collect();
+        Ok(HTML::tag(name_id, content))
+    }
+    
+    /// Parses a single item, such as a number, an identifier, an operator, a
+    /// group or a command.
+    fn atom(&mut self) -> Result<HTML, String> {
+        let Some(c) = self.peek() else {
+            return Err("unexpected end of source".to_string());
+        };
+        match c {
+            '{' => self.group(),
+            '\\' => self.command_atom(),
+            '0'..='9' => {
+                // a number may contain one decimal point, but not end with one
+                let rest = &self.src[self.pos..];
+                let mut seen_point = false;
+                let len = rest.find(|c: char| match c {
+                        '.' if !seen_point => { seen_point = true; false },
+                        c => !c.is_ascii_digit(),
+                    })
+                    .unwrap_or(rest.len());
+                let number = rest[..len].trim_end_matches('.');
+                self.pos += number.len();
+                Ok(leaf(str_ids::MN, number))
+            },
+            '}' => Err("unmatched '}'".to_string()),
+            '^' | '_' => Err(format!("unexpected '{c}'")),
+            '\'' => {
+                self.pos += 1;
+                Ok(leaf(str_ids::MO, "\u{2032}"))
+            },
+            c if c.is_alphabetic() => {
+                self.pos += c.len_utf8();
+                Ok(leaf(str_ids::MI, &c.to_string()))
+            },
+            '-' => {
+                self.pos += 1;
+                Ok(leaf(str_ids::MO, "\u{2212}"))
+            },
+            c => {
+                self.pos += c.len_utf8();
+                Ok(leaf(str_ids::MO, &c.to_string()))
+            },
+        }
+    }
+    
+    fn command_atom(&mut self) -> Result<HTML, String> {
+        let Some(name) = self.command() else {
+            return Err("expected command after '\\'".to_string());
+        };
+        
+        if let Some(s) = greek_letter(name) {
+            let mi = Tag::new(str_ids::MI, HTML::text(s));
+            let is_upper = name.starts_with(|c: char| c.is_ascii_uppercase());
+            return Ok(if is_upper { mi.str_attr(str_ids::MATHVARIANT, "normal") } else { mi }.into());
+        } else if let Some(s) = identifier_symbol(name) {
+            return Ok(leaf(str_ids::MI, s));
+        } else if let Some(s) = operator_symbol(name) {
+            return Ok(leaf(str_ids::MO, s));
+        } else if is_function_name(name) {
+            return Ok(leaf(str_ids::MI, name));
+        } else if let Some(width) = space_width(name) {
+            return Ok(Tag::new(str_ids::MSPACE, HTML::Empty)
+                .str_attr(str_ids::WIDTH, width)
+                .into());
+        } else if let Some((accent, under)) = accent(name) {
+            let base = self.arg()?;
+            let (name_id, attr) = if under { (str_ids::MUNDER, str_ids::ACCENTUNDER) } else { (str_ids::MOVER, str_ids::ACCENT) };
+            return Ok(Tag::new(name_id, [base, leaf(str_ids::MO, accent)].into_iter().collect())
+                .str_attr(attr, "true")
+                .into());
+        }
+        
+        match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let num = self.arg()?;
+                let den = self.arg()?;
+                Ok(HTML::tag(str_ids::MFRAC, [num, den].into_iter().collect()))
+            },
+            "binom" => {
+                let n = self.arg()?;
+                let k = self.arg()?;
+                let frac = Tag::new(str_ids::MFRAC, [n, k].into_iter().collect())
+                    .str_attr(str_ids::LINETHICKNESS, "0");
+                Ok(mrow(vec![leaf(str_ids::MO, "("), frac.into(), leaf(str_ids::MO, ")")]))
+            },
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.peek() == Some('[') {
+                    let src = self.optional_text_arg()?;
+                    let (items, _) = MathParser {src, pos: 0, display: self.display}.row(false)?;
+                    Some(mrow(items))
+                } else {
+                    None
+                };
+                let radicand = self.arg()?;
+                Ok(match index {
+                    Some(index) => HTML::tag(str_ids::MROOT, [radicand, index].into_iter().collect()),
+                    None => HTML::tag(str_ids::MSQRT, radicand),
+                })
+            },
+            "text" | "textrm" | "mbox" => {
+                let text = self.text_arg()?;
+                Ok(leaf(str_ids::MTEXT, text))
+            },
+            "operatorname" => {
+                let text = self.text_arg()?;
+                Ok(leaf(str_ids::MI, text.trim()))
+            },
+            "mathrm" | "mathbf" | "mathit" | "mathbb" | "mathcal" | "mathsf" | "mathtt" | "mathfrak" => {
+                let variant = match name {
+                    "mathrm" => "normal",
+                    "mathbf" => "bold",
+                    "mathit" => "italic",
+                    "mathbb" => "double-struck",
+                    "mathcal" => "script",
+                    "mathsf" => "sans-serif",
+                    "mathtt" => "monospace",
+                    _ => "fraktur",
+                };
+                let arg = self.arg()?;
+                Ok(set_variant(arg, variant))
+            },
+            "left" => {
+                let open = self.delimiter()?;
+                let (mut items, _) = self.row(false)?;
+                if self.command() != Some("right") {
+                    return Err("'\\left' without matching '\\right'".to_string());
+                }
+                let close = self.delimiter()?;
+                if let Some(open) = open {
+                    items.insert(0, leaf(str_ids::MO, open));
+                }
+                if let Some(close) = close {
+                    items.push(leaf(str_ids::MO, close));
+                }
+                Ok(HTML::tag(str_ids::MROW, items.into_iter().collect()))
+            },
+            "begin" => self.environment(),
+            "right" => Err("'\\right' without matching '\\left'".to_string()),
+            "end" => Err("'\\end' without matching '\\begin'".to_string()),
+            "%" | "$" | "&" | "#" | "_" => Ok(leaf(str_ids::MO, name)),
+            _ => Err(format!("unknown command '\\{name}'")),
+        }
+    }
+    
+    /// Parses the delimiter after `\left` or `\right`, which is `None` for
+    /// the empty delimiter `.`.
+    fn delimiter(&mut self) -> Result<Option<&'static str>, String> {
+        self.skip_whitespace();
+        if self.peek() == Some('\\') {
+            let name = self.command().unwrap_or_default();
+            return match name {
+                "{" | "}" | "|" | "langle" | "rangle" | "lfloor" | "rfloor" | "lceil" | "rceil" => Ok(operator_symbol(name)),
+                _ => Err(format!("invalid delimiter '\\{name}'")),
+            };
+        }
+        let d = match self.peek() {
+            Some('.') => None,
+            Some('(') => Some("("),
+            Some(')') => Some(")"),
+            Some('[') => Some("["),
+            Some(']') => Some("]"),
+            Some('|') => Some("|"),
+            Some('/') => Some("/"),
+            Some(c) => return Err(format!("invalid delimiter '{c}'")),
+            None => return Err("expected delimiter, was end of source".to_string()),
+        };
+        self.pos += 1;
+        Ok(d)
+    }
+    
+    /// Parses an environment such as `matrix` or `cases`, after `\begin`.
+    fn environment(&mut self) -> Result<HTML, String> {
+        let name = self.text_arg()?.trim();
+        let (open, close, align) = match name {
+            "matrix" => (None, None, None),
+            "pmatrix" => (Some("("), Some(")"), None),
+            "bmatrix" => (Some("["), Some("]"), None),
+            "Bmatrix" => (Some("{"), Some("}"), None),
+            "vmatrix" => (Some("|"), Some("|"), None),
+            "Vmatrix" => (Some("\u{2016}"), Some("\u{2016}"), None),
+            "cases" => (Some("{"), None, Some("left left")),
+            "aligned" | "align" | "align*" | "split" => (None, None, Some("right left")),
+            _ => return Err(format!("unknown environment '{name}'")),
+        };
+        
+        let mut rows = Vec::new();
+        let mut cells = Vec::new();
+        loop {
+            let (items, end) = self.row(true)?;
+            if end == RowEnd::Close && items.is_empty() && cells.is_empty() && !rows.is_empty() {
+                // a trailing `\\` does not begin a new row
+                break;
+            }
+            cells.push(HTML::tag(str_ids::MTD, mrow(items)));
+            if end != RowEnd::Cell {
+                rows.push(HTML::tag(str_ids::MTR, std::mem::take(&mut cells).into_iter().collect()));
+            }
+            if end == RowEnd::Close {
+                break;
+            }
+        }
+        if self.command() != Some("end") || self.text_arg()?.trim() != name {
+            return Err(format!("'\\begin{{{name}}}' without matching '\\end{{{name}}}'"));
+        }
+        
+        let mut table = Tag::new(str_ids::MTABLE, rows.into_iter().collect());
+        if let Some(align) = align {
+            table = table.str_attr(str_ids::COLUMNALIGN, align);
+        }
+        let mut items = vec![table.into()];
+        if let Some(open) = open {
+            items.insert(0, leaf(str_ids::MO, open));
+        }
+        if let Some(close) = close {
+            items.push(leaf(str_ids::MO, close));
+        }
+        Ok(mrow(items))
+    }
+}
+
+/// Converts TeX math source into a MathML `<math>` element. Display math is
+/// rendered as a block, with the limits of operators such as `\sum` placed
+/// above and below them. The TeX source is kept in the `alttext` attribute,
+/// which is used when rendering as text.
+pub(super) fn tex_to_mathml(src: &str, display: bool) -> Result<Tag, errors::RuntimeError> {
+    let mut parser = MathParser {src, pos: 0, display};
+    let (items, _) = parser.row(false)
+        .map_err(errors::RuntimeError::MathSyntaxError)?;
+    if let Some(c) = parser.peek() {
+        let e = if c == '}' { "unmatched '}'".to_string() } else { format!("unexpected '\\{}'", parser.command().unwrap_or_default()) };
+        return Err(errors::RuntimeError::MathSyntaxError(e));
+    }
+    
+    let mut tag = Tag::new(str_ids::MATH, mrow(items));
+    if display {
+        tag = tag.str_attr(str_ids::DISPLAY, "block");
+    }
+    Ok(tag.str_attr(str_ids::ALTTEXT, src.trim()))
+}
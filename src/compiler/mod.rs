@@ -1,29 +1,57 @@
 //! This module contains the backend of the Papyri compiler; it is responsible
 //! for compiling an abstract syntax tree into HTML (or plain text).
 
+mod a11y;
+mod assets;
 mod base;
+mod bibliography;
+mod capabilities;
 mod context;
+mod data;
 mod exports;
+mod fetch;
+mod footnotes;
 mod frame;
 mod func;
 mod highlight;
 mod highlight_papyri;
 mod html;
+mod labels;
+mod limits;
+mod links;
+mod markdown;
+mod math;
 mod matcher;
 mod module_loader;
 mod names;
 mod native;
+mod native_custom;
 mod native_gen;
 mod regex_value;
 mod render;
+mod sanitize;
 mod sequence;
 mod signature;
+mod table;
 mod tag;
+mod testing;
+mod toc;
 mod types;
+mod validate;
 mod value;
 mod value_convert;
 
-pub use base::CompileResult;
+pub use base::{CompileResult, Document};
+pub use capabilities::Capabilities;
+pub use fetch::FetchOptions;
 pub use context::Context;
 pub use html::HTML;
+pub use labels::CrossReferences;
+pub use limits::Limits;
+pub use links::LinkChecker;
+pub use native_custom::{NativeArgs, NativeFuncBuilder};
+pub use render::{OutFile, RenderMode};
+pub use tag::Tag;
+pub use testing::{TestExpectation, TestOutcome};
 pub use value::Value;
+pub use value_convert::TryConvert;
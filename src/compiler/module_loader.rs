@@ -1,4 +1,4 @@
-use std::{fs, path};
+use std::path;
 use std::rc::Rc;
 use indexmap::IndexMap;
 
@@ -6,6 +6,7 @@ use crate::errors::{ModuleError, PapyriResult};
 use crate::parser;
 use crate::utils::{sourcefile, taginfo};
 use super::base::{Compiler, CompileResult};
+use super::bibliography::Bibliography;
 use super::context::Context;
 use super::frame::{InactiveFrame, ActiveFrame};
 use super::html::HTML;
@@ -20,16 +21,31 @@ pub struct ModuleCache {
     /// library itself.
     stdlib: Option<InactiveFrame>,
     
+    /// The ID of the standard library's source file, once it has been
+    /// loaded.
+    pub(super) stdlib_src_id: Option<sourcefile::SourceFileID>,
+    
     /// The cache of compiled modules.
     cache: IndexMap<Box<path::Path>, ModuleState, fxhash::FxBuildHasher>,
 }
 
 type CachedCompileResult = (HTML, RcDict);
 
+#[derive(Debug, Clone)]
+/// A compiled module, held in the module cache.
+struct CachedModule {
+    result: CachedCompileResult,
+    
+    /// The bibliography entries loaded when compiling this module, including
+    /// by the modules it imports. The bibliography is cleared between compile
+    /// jobs, so these are loaded again whenever the cached module is used.
+    bibliography: Bibliography,
+}
+
 #[derive(Debug, Clone)]
 enum ModuleState {
     NotLoaded,
-    Loaded(CachedCompileResult),
+    Loaded(CachedModule),
     Busy,
     Error,
 }
@@ -46,6 +62,7 @@ impl ModuleCache {
     pub fn new() -> ModuleCache {
         ModuleCache {
             stdlib: None,
+            stdlib_src_id: None,
             cache: IndexMap::default(),
         }
     }
@@ -79,19 +96,21 @@ impl Context {
     
     /// Compiles a Papyri source file.
     pub fn compile(&mut self, src: Rc<sourcefile::SourceFile>) -> CompileResult {
-        self._compile(src, taginfo::ContentKind::REQUIRE_P)
+        let mut result = self._compile(src, taginfo::ContentKind::REQUIRE_P);
+        result.out = self.resolve_deferred(result.out);
+        result
     }
     
-    /// Loads a Papyri source file from the filesystem and compiles it. This
+    /// Loads a Papyri source file from the file system and compiles it. This
     /// only fails if the source file cannot be read; any other errors which
     /// occur during compilation are reported through `self.diagnostics`.
     pub fn load_uncached(&mut self, path: &path::Path) -> PapyriResult<CompileResult> {
-        self.source_files.load_from_path(path)
+        self.source_files.load_from_path(self.file_system.as_ref(), path)
             .map(|src| self.compile(src))
             .map_err(|e| ModuleError::IOError(path.into(), e).into())
     }
     
-    /// Loads a Papyri source file from the filesystem and compiles it, or
+    /// Loads a Papyri source file from the file system and compiles it, or
     /// returns a cached result if the source file has already been loaded and
     /// compiled. This fails if the source file cannot be read, if a circular
     /// import is detected, or if either of those two failures occurred during
@@ -102,7 +121,7 @@ impl Context {
     /// by another Papyri source file.
     pub fn load_cached(&mut self, path: path::PathBuf) -> PapyriResult<CachedCompileResult> {
         // use `match` instead of `.map_err` here because otherwise rustc thinks `path` is moved
-        let k = match fs::canonicalize(&path) {
+        let k = match self.file_system.canonicalize(&path) {
             Ok(canonical_path) => canonical_path,
             Err(e) => {
                 let e = ModuleError::IOError(path.into(), e);
@@ -116,19 +135,31 @@ impl Context {
                 
                 // compile with no `out_files`
                 let old_out_files = std::mem::take(&mut self.out_files);
-                let result = self.load_uncached(&path)
-                    .map(|r| (r.out, Rc::new(r.exports)));
+                let old_bibliography = self.bibliography.clone();
+                // a module's table of contents is resolved as part of the
+                // document which includes it
+                let result = self.source_files.load_from_path(self.file_system.as_ref(), &path)
+                    .map(|src| self._compile(src, taginfo::ContentKind::REQUIRE_P))
+                    .map(|r| (r.out, Rc::new(r.exports)))
+                    .map_err(|e| ModuleError::IOError(path.clone().into(), e).into());
                 
                 self.out_files = old_out_files;
                 
+                let bibliography: Bibliography = self.bibliography.iter()
+                    .filter(|&(k, v)| !old_bibliography.get(k).is_some_and(|old| Rc::ptr_eq(old, v)))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
                 let state = result.as_ref()
                     .cloned()
-                    .map_or(ModuleState::Error, ModuleState::Loaded);
+                    .map_or(ModuleState::Error, |result| ModuleState::Loaded(CachedModule {result, bibliography}));
                 
                 self.module_cache.set_by_index(index, state);
                 result
             },
-            ModuleState::Loaded(cached_result) => Ok(cached_result),
+            ModuleState::Loaded(module) => {
+                self.bibliography.extend(module.bibliography);
+                Ok(module.result)
+            },
             ModuleState::Busy => Err(ModuleError::CircularImport(path.into()).into()),
             ModuleState::Error => Err(ModuleError::PreviousError(path.into()).into()),
         }
@@ -140,6 +171,7 @@ impl Context {
         use crate::errors;
         
         let src = self.source_files.load_synthetic("<stdlib>", include_str!("../std.papyri"));
+        self.module_cache.stdlib_src_id = Some(src.id);
         let result = self._compile(src, taginfo::ContentKind::RequireEmpty);
         let frame = ActiveFrame::new(
             Some(self.natives_frame.clone()),
@@ -156,11 +188,11 @@ impl Context {
     
     fn _compile(&mut self, src: Rc<sourcefile::SourceFile>, content_kind: taginfo::ContentKind) -> CompileResult {
         let root = parser::parse(src, &mut self.diagnostics, &mut self.string_pool);
+        self.budget.begin(&self.limits);
         let mut compiler = Compiler::new(self);
         let out = compiler.compile_sequence(&root, content_kind);
-        CompileResult {
-            out,
-            exports: compiler.exports,
-        }
+        let exports = compiler.exports;
+        self.budget.end();
+        CompileResult {out, exports}
     }
 }
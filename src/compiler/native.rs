@@ -1,14 +1,17 @@
-use reqwest::blocking::Client;
 use crate::compiler::html;
 use crate::errors;
 use crate::utils::{str_ids, text, relpath};
 use crate::utils::sourcefile::{SourceRange, SourceFileID};
 use crate::parser::Type;
 use super::base::Compiler;
+use super::bibliography::CitationStyle;
 use super::func::Func;
 use super::html::HTML;
+use super::math;
 use super::regex_value::RcRegex;
+use super::render::{OutFile, RenderMode};
 use super::tag::Tag;
+use super::testing::TestExpectation;
 use super::value::{Value, Int, RcStr, List, RcDict};
 use super::value_convert::TryConvert;
 
@@ -189,6 +192,15 @@ crate::native_defs! {
         fn IS_WHITESPACE(HTML: content HTML) {
             HTML.is_whitespace()
         }
+        
+        fn PARSE(XML: named bool = false, SOURCE: content RcStr) {
+            compiler.parse_html_impl(&SOURCE, XML)?
+        }
+        
+        @bind_content
+        fn SANITIZE(HTML: content HTML) {
+            HTML.sanitized()
+        }
     }
     
     impl LIST for List {
@@ -384,9 +396,12 @@ crate::native_defs! {
     
     impl FILE {
         fn LIST(PATH: content RcStr) {
-            let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), false);
+            compiler.require_capability(|c| c.file_list, "@file::list")?;
+            let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), false)?;
             let base_path = PATH.trim_end_matches('/');
+            let fs = compiler.ctx.file_system.clone();
             relpath::find_papyri_source_files_in_dir(
+                    fs.as_ref(),
                     &path,
                     |p, e| { compiler.report_static(errors::ModuleError::IOError(p.into(), e), call_range); },
                 )?
@@ -400,40 +415,149 @@ crate::native_defs! {
         }
         
         fn READ(PATH: content RcStr) {
-            let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), false);
-            std::fs::read_to_string(path)
-                .map_err(|e| errors::RuntimeError::FileReadError(PATH, e))?
+            compiler.require_capability(|c| c.file_read, "@file::read")?;
+            let (_, text) = compiler.read_file_impl(PATH, call_range)?;
+            text
+        }
+        
+        fn READ_CSV(PATH: content RcStr) {
+            compiler.require_capability(|c| c.file_read, "@file::read_csv")?;
+            let (_, text) = compiler.read_file_impl(PATH, call_range)?;
+            compiler.parse_csv(&text)?
+        }
+        
+        fn READ_HTML(XML: named Option<bool> = (), PATH: content RcStr) {
+            compiler.require_capability(|c| c.file_read, "@file::read_html")?;
+            let (path, text) = compiler.read_file_impl(PATH, call_range)?;
+            let xml = XML.unwrap_or_else(|| path.extension()
+                .is_some_and(|ext| ext == "xml" || ext == "svg")
+            );
+            compiler.parse_html_impl(&text, xml)?
         }
         
-        fn WRITE(PATH: positional RcStr, HTML: content HTML) {
-            compiler.ctx.push_out_file(PATH, HTML)?
+        fn READ_JSON(PATH: content RcStr) {
+            compiler.require_capability(|c| c.file_read, "@file::read_json")?;
+            let (_, text) = compiler.read_file_impl(PATH, call_range)?;
+            compiler.parse_json(&text)?
+        }
+        
+        fn READ_TOML(PATH: content RcStr) {
+            compiler.require_capability(|c| c.file_read, "@file::read_toml")?;
+            let (_, text) = compiler.read_file_impl(PATH, call_range)?;
+            compiler.parse_toml(&text)?
+        }
+        
+        fn WRITE(PATH: positional RcStr, MODE: named Option<RcStr> = (), HTML: content HTML) {
+            compiler.require_capability(|c| c.file_write, "@file::write")?;
+            let mode = match MODE {
+                Some(mode) => Some(RenderMode::from_name(mode.as_ref())
+                    .ok_or(errors::RuntimeError::InvalidRenderMode(mode))?),
+                None => None,
+            };
+            compiler.ctx.push_out_file(PATH, OutFile::HTML(HTML, mode))?
+        }
+        
+        fn WRITE_JSON(PATH: positional RcStr, PRETTY: named bool = false, VALUE: content Value) {
+            compiler.require_capability(|c| c.file_write, "@file::write_json")?;
+            let text = compiler.encode_json(VALUE, PRETTY)?;
+            compiler.ctx.push_out_file(PATH, OutFile::Bytes(text.into_bytes().into()))?
         }
     }
 
+    impl ASSET {
+        fn URL(HASH: named bool = false, PATH: content RcStr) {
+            compiler.require_capability(|c| c.file_read && c.file_write, "@asset::url")?;
+            compiler.copy_asset(PATH, HASH, call_range)?
+        }
+    }
+    
+    impl BIB {
+        fn LOAD(PATH: content RcStr) {
+            compiler.require_capability(|c| c.file_read, "@bib::load")?;
+            let (path, text) = compiler.read_file_impl(PATH, call_range)?;
+            compiler.load_bibliography(&path, &text)?
+        }
+    }
+    
+    impl FOOTNOTES {
+        fn NOTE(BODY: content HTML) {
+            if BODY.is_block() {
+                let e = errors::TypeError::ExpectedWas(Type::Inline, Type::Block);
+                return Err(e.into());
+            }
+            Tag::new(str_ids::_FOOTNOTE, BODY)
+        }
+        
+        fn RENDER() {
+            Tag::new(str_ids::_FOOTNOTES, HTML::Empty)
+        }
+    }
+    
+    impl JSON {
+        fn ENCODE(PRETTY: named bool = false, VALUE: content Value) {
+            compiler.encode_json(VALUE, PRETTY)?
+        }
+    }
+    
     impl FETCH {
-        fn RAW(PATH: content RcStr) {
-            Client::builder()
-                .user_agent("Mozilla/5.0 (compatible) Papyri")
-                .build()
-                .map_err(|e| errors::RuntimeError::NetworkError(e))?
-                .get(PATH.as_ref())
-                .send()
-                .map_err(|e| errors::RuntimeError::NetworkError(e))?
-                .text()
-                .map_err(|e| errors::RuntimeError::NetworkError(e))?
+        fn HTML(METHOD: named RcStr = "GET", HEADERS: named Option<RcDict> = (), BODY: named Option<RcStr> = (), STATUS: named Option<Int> = (), SANITIZE: named bool = false, PATH: content RcStr) {
+            compiler.require_capability(|c| c.fetch, "@fetch::html")?;
+            let request = compiler.fetch_request(PATH, METHOD, HEADERS, BODY, STATUS)?;
+            let text = compiler.fetch_text(&request)?;
+            let html = html::parse_html(&text, compiler.string_pool_mut())?;
+            if SANITIZE { html.sanitized() } else { html }
         }
-
-        fn HTML(PATH: content RcStr) {
-            let text = Client::builder()
-                .user_agent("Mozilla/5.0 (compatible) Papyri")
-                .build()
-                .map_err(|e| errors::RuntimeError::NetworkError(e))?
-                .get(PATH.as_ref())
-                .send()
-                .map_err(|e| errors::RuntimeError::NetworkError(e))?
-                .text()
-                .map_err(|e| errors::RuntimeError::NetworkError(e))?;
-            html::parse_html(&text, compiler.string_pool_mut())?
+        
+        fn JSON(METHOD: named RcStr = "GET", HEADERS: named Option<RcDict> = (), BODY: named Option<RcStr> = (), STATUS: named Option<Int> = (), PATH: content RcStr) {
+            compiler.require_capability(|c| c.fetch, "@fetch::json")?;
+            let request = compiler.fetch_request(PATH, METHOD, HEADERS, BODY, STATUS)?;
+            let text = compiler.fetch_text(&request)?;
+            compiler.parse_json(&text)?
+        }
+        
+        fn RAW(METHOD: named RcStr = "GET", HEADERS: named Option<RcDict> = (), BODY: named Option<RcStr> = (), STATUS: named Option<Int> = (), PATH: content RcStr) {
+            compiler.require_capability(|c| c.fetch, "@fetch::raw")?;
+            let request = compiler.fetch_request(PATH, METHOD, HEADERS, BODY, STATUS)?;
+            compiler.fetch_text(&request)?
+        }
+    }
+    
+    impl TEST {
+        fn ERR(KIND: positional RcStr, NAME: named Option<RcStr> = (), SOURCE: content RcStr) {
+            compiler.native_test_impl(NAME, TestExpectation::Error(KIND), SOURCE, call_range)?
+        }
+        
+        fn OK(EXPECTED: positional RcStr, NAME: named Option<RcStr> = (), SOURCE: content RcStr) {
+            compiler.native_test_impl(NAME, TestExpectation::Output(EXPECTED), SOURCE, call_range)?
+        }
+    }
+    
+    fn BIBLIOGRAPHY(STYLE: named Option<RcStr> = ()) {
+        let style = STYLE.unwrap_or_else(|| "author-year".into());
+        if CitationStyle::from_name(&style).is_none() {
+            let e = errors::RuntimeError::InvalidCitationStyle(style);
+            return Err(e.into());
+        }
+        Tag::new(str_ids::_BIBLIOGRAPHY, HTML::Empty)
+            .str_attr(str_ids::STYLE, &style)
+    }
+    
+    fn CITE(KEYS: pos_spread Vec<RcStr>, HTML: content HTML) {
+        if HTML.is_block() {
+            let e = errors::TypeError::ExpectedWas(Type::Inline, Type::Block);
+            return Err(e.into());
+        }
+        if KEYS.is_empty() {
+            // without keys, the content is the title of a cited work, not a
+            // citation key; a key here is most likely a mistake
+            if let HTML::Text(text) = &HTML {
+                if compiler.ctx.bibliography.contains_key(text) {
+                    compiler.report_static(errors::Warning::CiteContentIsKey(text.clone()), call_range);
+                }
+            }
+            Tag::new(str_ids::CITE, HTML)
+        } else {
+            compiler.cite_impl(KEYS, HTML)?
         }
     }
     
@@ -442,13 +566,13 @@ crate::native_defs! {
     }
     
     fn IMPORT(PATH: content RcStr) {
-        let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), true);
+        let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), true)?;
         let (_, module_exports) = compiler.ctx.load_cached(path)?;
         module_exports
     }
     
     fn INCLUDE(PATH: content RcStr) {
-        let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), true);
+        let path = compiler.resolve_relative_path(call_range.src_id, PATH.as_ref(), true)?;
         let (module_out, module_exports) = compiler.ctx.load_cached(path)?;
         
         for (&k, v) in module_exports.as_ref().iter() {
@@ -457,10 +581,34 @@ crate::native_defs! {
         module_out
     }
     
+    fn LABEL(NAME: positional RcStr, KIND: named Option<RcStr> = (), HTML: content HTML) {
+        compiler.label_impl(NAME, KIND, HTML, call_range)?
+    }
+    
+    fn MATH(DISPLAY: named bool = false, SOURCE: content RcStr) {
+        math::tex_to_mathml(&SOURCE, DISPLAY)?
+    }
+    
     fn RAISE(STR: content RcStr) {
         let e = errors::RuntimeError::Raised(STR);
         return Err(e.into());
     }
+    
+    fn REF(NAME: content RcStr) {
+        let mut tag = Tag::new(str_ids::_REF, HTML::text("??"))
+            .str_attr(str_ids::NAME, &NAME);
+        tag.range = Some(call_range);
+        tag
+    }
+    
+    fn TABLE(HEADER_ROWS: named Int = 1, ALIGN: named Option<RcStr> = (), CAPTION: named Option<HTML> = (), DECIMALS: named Option<Int> = (), DATA: content Value) {
+        compiler.table_impl(DATA, HEADER_ROWS, ALIGN, CAPTION, DECIMALS)?
+    }
+    
+    fn TOC(MAX_LEVEL: named Int = 6) {
+        Tag::new(str_ids::_TOC, HTML::Empty)
+            .str_attr(str_ids::MAX_LEVEL, &MAX_LEVEL.to_string())
+    }
 }
 
 impl <'a> Compiler<'a> {
@@ -545,13 +693,16 @@ impl <'a> Compiler<'a> {
         tag
     }
     
-    fn escape_html_impl(&mut self, h: HTML) -> String {
+    pub(super) fn escape_html_impl(&mut self, h: HTML) -> String {
         let mut s = Vec::new();
         self.ctx.render(&h, true, &mut s).unwrap();
         String::from_utf8(s).unwrap()
     }
     
-    fn resolve_relative_path(&mut self, src_id: SourceFileID, relative_path: &str, add_papyri_suffix: bool) -> std::path::PathBuf {
+    /// Resolves a path relative to the directory of the given source file.
+    /// Fails if the resulting path is outside of the root directory allowed by
+    /// this compiler's context.
+    pub(super) fn resolve_relative_path(&mut self, src_id: SourceFileID, relative_path: &str, add_papyri_suffix: bool) -> errors::PapyriResult<std::path::PathBuf> {
         let mut path = self.ctx.source_files
             .get(src_id)
             .path
@@ -563,7 +714,30 @@ impl <'a> Compiler<'a> {
         } else {
             path.push(relative_path);
         }
-        path
+        self.require_path_in_root(&path, relative_path)?;
+        Ok(path)
+    }
+    
+    /// Reads the file at the given path, relative to the directory of the
+    /// source file where the call occurs. Returns the resolved path along with
+    /// the file's contents.
+    fn read_file_impl(&mut self, path_str: RcStr, call_range: SourceRange) -> errors::PapyriResult<(std::path::PathBuf, String)> {
+        let path = self.resolve_relative_path(call_range.src_id, path_str.as_ref(), false)?;
+        let text = self.ctx.file_system.read_to_string(&path)
+            .map_err(|e| errors::RuntimeError::FileReadError(path_str, e))?;
+        Ok((path, text))
+    }
+    
+    /// Parses the given string as HTML, which may be either a complete
+    /// document or a fragment, or as XML.
+    fn parse_html_impl(&mut self, text: &str, xml: bool) -> errors::PapyriResult<HTML> {
+        let pool = self.string_pool_mut();
+        let html = if xml {
+            html::parse_xml(text, pool)?
+        } else {
+            html::parse_html_document_or_fragment(text, pool)?
+        };
+        Ok(html)
     }
     
     fn eval_callback<T: TryConvert>(&mut self, callback: Func, arg: Value, call_range: SourceRange) -> errors::PapyriResult<T> {
@@ -0,0 +1,224 @@
+use std::rc::Rc;
+
+use crate::errors;
+use crate::parser::Type;
+use crate::utils::NameID;
+use super::base::Compiler;
+use super::context::Context;
+use super::func::Func;
+use super::signature::{FuncParam, FuncSignature};
+use super::value::{Value, Dict, RcStr};
+use super::value_convert::TryConvert;
+
+type CustomNativeImpl = dyn Fn(&mut NativeArgs) -> errors::PapyriResult<Value>;
+
+/// A native function registered by an application embedding the Papyri
+/// compiler, via `Context::native_func`.
+pub struct CustomNativeFunc {
+    pub(super) name_id: NameID,
+    pub(super) signature: super::signature::RcFuncSignature,
+    f: Box<CustomNativeImpl>,
+}
+
+impl std::fmt::Debug for CustomNativeFunc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomNativeFunc")
+            .field("name_id", &self.name_id)
+            .field("signature", &self.signature)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A builder for declaring a native function with typed parameters. Create
+/// one using `Context::native_func`, declare the function's parameters, then
+/// call `register` with the function's implementation.
+pub struct NativeFuncBuilder<'a> {
+    ctx: &'a mut Context,
+    path: Vec<NameID>,
+    signature: FuncSignature,
+}
+
+impl <'a> NativeFuncBuilder<'a> {
+    fn param(&mut self, name: &str, type_: Type, default_value: Option<Value>) -> FuncParam {
+        let name_id = self.ctx.string_pool.insert(name);
+        let param = FuncParam::new(name_id, type_);
+        match default_value {
+            Some(v) => param.with_default(v),
+            None => param,
+        }
+    }
+    
+    /// Declares a required positional parameter.
+    pub fn positional(mut self, name: &str, type_: Type) -> Self {
+        let param = self.param(name, type_, None);
+        self.signature = self.signature.positional(param);
+        self
+    }
+    
+    /// Declares an optional positional parameter, with a default value.
+    pub fn positional_default(mut self, name: &str, type_: Type, default_value: Value) -> Self {
+        let param = self.param(name, type_, Some(default_value));
+        self.signature = self.signature.positional(param);
+        self
+    }
+    
+    /// Declares a required named parameter.
+    pub fn named(mut self, name: &str, type_: Type) -> Self {
+        let param = self.param(name, type_, None);
+        self.signature = self.signature.named(param);
+        self
+    }
+    
+    /// Declares an optional named parameter, with a default value.
+    pub fn named_default(mut self, name: &str, type_: Type, default_value: Value) -> Self {
+        let param = self.param(name, type_, Some(default_value));
+        self.signature = self.signature.named(param);
+        self
+    }
+    
+    /// Declares an implicit named parameter, which takes its value from an
+    /// implicit variable of the same name if no argument is given.
+    pub fn implicit(mut self, name: &str, type_: Type) -> Self {
+        let param = self.param(name, type_, None);
+        self.signature = self.signature.implicit(param);
+        self
+    }
+    
+    /// Declares an implicit named parameter, with a default value which is
+    /// used if there is no argument and no implicit variable of that name.
+    pub fn implicit_default(mut self, name: &str, type_: Type, default_value: Value) -> Self {
+        let param = self.param(name, type_, Some(default_value));
+        self.signature = self.signature.implicit(param);
+        self
+    }
+    
+    /// Declares a parameter collecting any remaining positional arguments, as
+    /// a list. The type should be a list type.
+    pub fn pos_spread(mut self, name: &str, type_: Type) -> Self {
+        let param = self.param(name, type_, None);
+        self.signature = self.signature.pos_spread(param);
+        self
+    }
+    
+    /// Declares a parameter collecting any remaining named arguments, as a
+    /// dictionary. The type should be a dictionary type.
+    pub fn named_spread(mut self, name: &str, type_: Type) -> Self {
+        let param = self.param(name, type_, None);
+        self.signature = self.signature.named_spread(param);
+        self
+    }
+    
+    /// Declares the content parameter. If this is not declared, then the
+    /// function does not accept content.
+    pub fn content(mut self, name: &str, type_: Type) -> Self {
+        let param = self.param(name, type_, None);
+        self.signature = self.signature.content(param);
+        self
+    }
+    
+    /// Registers the function with the given implementation, making it
+    /// available to all Papyri source files compiled in this context. Any
+    /// existing native function of the same name is replaced, but functions
+    /// declared in the standard library or in a source file take precedence.
+    pub fn register<F>(self, f: F) where F: Fn(&mut NativeArgs) -> errors::PapyriResult<Value> + 'static {
+        let (&name_id, namespace) = self.path.split_last()
+            .unwrap_or_else(|| errors::ice("Native function path is empty"));
+        
+        let func = Func::CustomNative(Rc::new(CustomNativeFunc {
+            name_id,
+            signature: self.signature.build(),
+            f: Box::new(f),
+        }));
+        
+        let frame = &self.ctx.natives_frame;
+        let Some((&first, rest)) = namespace.split_first() else {
+            frame.set(name_id, func.into());
+            return;
+        };
+        let old = frame.get_local(first);
+        frame.set(first, insert_in_namespace(old, rest, name_id, func.into()));
+    }
+}
+
+/// Inserts a value into a (possibly nested) namespace dictionary, returning
+/// the new dictionary. Namespaces which do not exist, or which are not
+/// dictionaries, are replaced with new dictionaries.
+fn insert_in_namespace(namespace: Option<Value>, path: &[NameID], name_id: NameID, value: Value) -> Value {
+    let mut dict = match namespace {
+        Some(Value::Dict(d)) => d.as_ref().clone(),
+        _ => Dict::default(),
+    };
+    let value = match path.split_first() {
+        Some((&first, rest)) => insert_in_namespace(dict.get(&first).cloned(), rest, name_id, value),
+        None => value,
+    };
+    dict.insert(path.first().copied().unwrap_or(name_id), value);
+    dict.into()
+}
+
+/// The arguments bound to the parameters of a native function registered via
+/// `Context::native_func`, when it is called.
+pub struct NativeArgs<'a, 'b> {
+    compiler: &'a mut Compiler<'b>,
+    bindings: Dict,
+}
+
+impl <'a, 'b> NativeArgs<'a, 'b> {
+    /// Returns the value bound to the parameter of the given name. Optional
+    /// parameters which were not given an argument are bound to their default
+    /// values.
+    pub fn get(&self, name: &str) -> errors::PapyriResult<Value> {
+        self.compiler.ctx.string_pool
+            .get_id_if_present(name)
+            .and_then(|name_id| self.bindings.get(&name_id))
+            .cloned()
+            .ok_or_else(|| errors::NameError::NoSuchParameter(Rc::from(name)).into())
+    }
+    
+    /// Returns the value bound to the parameter of the given name, converted
+    /// to a Rust type. Reports a type error if the conversion fails.
+    pub fn get_as<T: TryConvert>(&self, name: &str) -> errors::PapyriResult<T> {
+        self.get(name)?
+            .try_convert()
+    }
+    
+    /// Returns the string name corresponding to a name ID, such as a key in a
+    /// dictionary value.
+    pub fn get_name(&self, name_id: NameID) -> RcStr {
+        self.compiler.get_name(name_id)
+    }
+    
+    /// Creates a dictionary value from string keys and values. The keys should
+    /// be valid identifiers, so that they can be accessed from Papyri source.
+    pub fn new_dict<K: AsRef<str>, I: IntoIterator<Item=(K, Value)>>(&mut self, entries: I) -> Value {
+        let pool = self.compiler.string_pool_mut();
+        entries.into_iter()
+            .map(|(k, v)| (pool.insert(k.as_ref()), v))
+            .collect::<Dict>()
+            .into()
+    }
+}
+
+impl Context {
+    /// Begins declaring a native function with the given name, which will be
+    /// available to all Papyri source files compiled in this context. The name
+    /// may be qualified by a namespace, e.g. `app::greet`, in which case the
+    /// function is called like `@app::greet`.
+    pub fn native_func(&mut self, name: &str) -> NativeFuncBuilder<'_> {
+        let path = name.split("::")
+            .map(|part| self.string_pool.insert(part))
+            .collect();
+        NativeFuncBuilder {
+            ctx: self,
+            path,
+            signature: FuncSignature::builder(),
+        }
+    }
+}
+
+impl <'a> Compiler<'a> {
+    pub(super) fn evaluate_custom_native_func(&mut self, f: &CustomNativeFunc, bindings: Dict) -> errors::PapyriResult<Value> {
+        let mut args = NativeArgs {compiler: self, bindings};
+        (f.f)(&mut args)
+    }
+}
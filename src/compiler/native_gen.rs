@@ -107,7 +107,7 @@ macro_rules! native_defs {
         }
         
         impl <'a> $crate::compiler::base::Compiler<'a> {
-            #[allow(non_snake_case, unreachable_code)]
+            #[allow(non_snake_case, unreachable_code, clippy::redundant_closure_call)]
             pub(super) fn evaluate_native_func(&mut self, f: NativeFunc, mut bindings: $crate::compiler::value::Dict, $call_range: $crate::utils::sourcefile::SourceRange) -> $crate::errors::PapyriResult<$crate::compiler::value::Value> {
                 use $crate::utils::{str_ids, NameID};
                 use $crate::errors;
@@ -118,16 +118,19 @@ macro_rules! native_defs {
                         .map(std::mem::take)
                         .unwrap_or_else(|| errors::ice("failed to unpack"))
                 };
-                errors::PapyriResult::Ok(match f {
-                    $($(NativeFunc::$type_name(native_names::$type_name::$m_name) => {
+                // Each function body is evaluated in its own closure, so that
+                // unoptimised builds don't allocate a stack frame big enough
+                // for every native function's locals on each native call.
+                match f {
+                    $($(NativeFunc::$type_name(native_names::$type_name::$m_name) => (|| {
                         $(let $m_param_name: $m_param_type = take(str_ids::$m_param_name).expect_convert();)*
-                        Value::from($m_body)
-                    })*)*
-                    $(NativeFunc::$f_name => {
+                        errors::PapyriResult::Ok(Value::from($m_body))
+                    })(),)*)*
+                    $(NativeFunc::$f_name => (|| {
                         $(let $f_param_name: $f_param_type = take(str_ids::$f_param_name).expect_convert();)*
-                        Value::from($f_body)
-                    })*
-                })
+                        errors::PapyriResult::Ok(Value::from($f_body))
+                    })(),)*
+                }
             }
             
             pub(super) fn evaluate_native_attr(&mut self, subject: $crate::compiler::value::Value, attr_id: $crate::utils::NameID) -> $crate::errors::PapyriResult<$crate::compiler::func::Func> {
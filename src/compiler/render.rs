@@ -1,22 +1,57 @@
 use std::io;
+use std::rc::Rc;
 
 use crate::utils::{StringPool, str_ids, taginfo, text};
 use super::context::Context;
 use super::html::HTML;
+use super::markdown;
 use super::tag::Tag;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The format in which HTML content is rendered.
+pub enum RenderMode {
+    /// Render as HTML, escaping special characters in text.
+    HTML,
+    
+    /// Render as plain text; tags are omitted, and block-level tags are
+    /// separated by newlines.
+    Text,
+    
+    /// Render as Markdown. Tags with no Markdown equivalent are rendered as
+    /// their contents.
+    Markdown,
+    
+    /// Render as HTML, but without escaping special characters in text. A
+    /// string is rendered exactly as-is.
+    Raw,
+}
+
+impl RenderMode {
+    /// Returns the render mode with the given name, which is one of `html`,
+    /// `text`, `markdown` or `raw`.
+    pub fn from_name(name: &str) -> Option<RenderMode> {
+        match name {
+            "html" => Some(RenderMode::HTML),
+            "text" => Some(RenderMode::Text),
+            "markdown" => Some(RenderMode::Markdown),
+            "raw" => Some(RenderMode::Raw),
+            _ => None,
+        }
+    }
+}
+
 /// Renders a compiled Papyri document to HTML (or plain text).
 struct Renderer<'a, T: io::Write> {
     string_pool: &'a StringPool,
-    as_html: bool,
+    mode: RenderMode,
     writer: &'a mut T,
 }
 
 impl <'a, T: io::Write> Renderer<'a, T> {
-    /// Creates a new renderer, which outputs to the given writer. If `as_html`
-    /// is false, the renderer will write plain text instead of HTML.
-    fn new(string_pool: &'a StringPool, as_html: bool, writer: &'a mut T) -> Renderer<'a, T> {
-        Renderer {string_pool, as_html, writer}
+    /// Creates a new renderer, which outputs to the given writer. The mode
+    /// should not be `RenderMode::Markdown`, which has its own renderer.
+    fn new(string_pool: &'a StringPool, mode: RenderMode, writer: &'a mut T) -> Renderer<'a, T> {
+        Renderer {string_pool, mode, writer}
     }
     
     /// Renders an HTML item to this renderer's output writer.
@@ -31,7 +66,7 @@ impl <'a, T: io::Write> Renderer<'a, T> {
                 }
             },
             HTML::Text(t) => {
-                if self.as_html {
+                if self.mode == RenderMode::HTML {
                     write!(self.writer, "{}", text::encode_entities(t, false))?;
                 } else {
                     write!(self.writer, "{t}")?;
@@ -49,8 +84,19 @@ impl <'a, T: io::Write> Renderer<'a, T> {
     }
     
     fn render_tag(&mut self, tag: &Tag) -> io::Result<()> {
+        if matches!(tag.name_id, str_ids::_LABEL | str_ids::_REF) {
+            // placeholders for cross-references are rendered as their content
+            return self.render(&tag.content);
+        }
+        
+        if let (RenderMode::Text, str_ids::MATH, Some(Some(tex))) = (self.mode, tag.name_id, tag.get_attr(str_ids::ALTTEXT)) {
+            // MathML has no plain text equivalent, so the TeX source is used
+            return write!(self.writer, "{tex}");
+        }
+        
         let name = self.string_pool.get(tag.name_id);
-        if self.as_html {
+        let as_html = self.mode != RenderMode::Text;
+        if as_html {
             write!(self.writer, "<{name}")?;
             for (&k, v) in tag.attributes.iter() {
                 let attr_name = self.string_pool.get(k)
@@ -64,7 +110,7 @@ impl <'a, T: io::Write> Renderer<'a, T> {
         }
         
         self.render(&tag.content)?;
-        if !self.as_html {
+        if !as_html {
             match tag.name_id {
                 str_ids::P => write!(self.writer, "\n\n")?,
                 str_ids::HR => write!(self.writer, "\n\u{2015}\n\n")?,
@@ -80,10 +126,46 @@ impl <'a, T: io::Write> Renderer<'a, T> {
     }
 }
 
+#[derive(Debug, Clone)]
+/// The contents of an output file, written by a function such as
+/// `@file::write`.
+pub enum OutFile {
+    /// HTML content, rendered in the given format. If the format is `None`,
+    /// the content is rendered as either HTML or plain text, according to the
+    /// application's options.
+    HTML(HTML, Option<RenderMode>),
+    
+    /// Content which is written as-is, such as a file written by
+    /// `@file::write_json`.
+    Bytes(Rc<[u8]>),
+}
+
 impl Context {
     /// Renders the given HTML content to the writer.
     pub fn render<T: io::Write>(&self, html: &HTML, as_html: bool, writer: &mut T) -> io::Result<()> {
-        Renderer::new(&self.string_pool, as_html, writer)
-            .render(html)
+        let mode = if as_html { RenderMode::HTML } else { RenderMode::Text };
+        self.render_with_mode(html, mode, writer)
+    }
+    
+    /// Renders the given HTML content to the writer, in the given format.
+    pub fn render_with_mode<T: io::Write>(&self, html: &HTML, mode: RenderMode, writer: &mut T) -> io::Result<()> {
+        if mode == RenderMode::Markdown {
+            let s = markdown::render_markdown(html, &self.string_pool);
+            writer.write_all(s.as_bytes())
+        } else {
+            Renderer::new(&self.string_pool, mode, writer)
+                .render(html)
+        }
+    }
+    
+    /// Renders the contents of an output file to the writer. If the file has
+    /// no render mode, then it is rendered as HTML, or as plain text if
+    /// `as_html` is false.
+    pub fn render_out_file<T: io::Write>(&self, file: &OutFile, as_html: bool, writer: &mut T) -> io::Result<()> {
+        match file {
+            OutFile::HTML(html, Some(mode)) => self.render_with_mode(html, *mode, writer),
+            OutFile::HTML(html, None) => self.render(html, as_html, writer),
+            OutFile::Bytes(bytes) => writer.write_all(bytes),
+        }
     }
 }
@@ -0,0 +1,201 @@
+use crate::utils::{str_ids, NameID};
+use super::html::HTML;
+use super::tag::{AttrMap, Tag};
+
+/// What the sanitiser does with a tag of a given name.
+enum TagPolicy {
+    /// The tag is kept, along with any allowed attributes.
+    Keep,
+    
+    /// The tag is removed, but its (sanitised) content is kept.
+    Unwrap,
+    
+    /// The tag is removed along with its content.
+    Drop,
+}
+
+fn tag_policy(name_id: NameID) -> TagPolicy {
+    match name_id {
+        str_ids::A |
+        str_ids::ABBR |
+        str_ids::ADDRESS |
+        str_ids::ARTICLE |
+        str_ids::ASIDE |
+        str_ids::B |
+        str_ids::BDI |
+        str_ids::BDO |
+        str_ids::BLOCKQUOTE |
+        str_ids::BR |
+        str_ids::CAPTION |
+        str_ids::CITE |
+        str_ids::CODE |
+        str_ids::COL |
+        str_ids::COLGROUP |
+        str_ids::DD |
+        str_ids::DEL |
+        str_ids::DETAILS |
+        str_ids::DFN |
+        str_ids::DIV |
+        str_ids::DL |
+        str_ids::DT |
+        str_ids::EM |
+        str_ids::FIGCAPTION |
+        str_ids::FIGURE |
+        str_ids::FOOTER |
+        str_ids::H1 |
+        str_ids::H2 |
+        str_ids::H3 |
+        str_ids::H4 |
+        str_ids::H5 |
+        str_ids::H6 |
+        str_ids::HEADER |
+        str_ids::HGROUP |
+        str_ids::HR |
+        str_ids::I |
+        str_ids::IMG |
+        str_ids::INS |
+        str_ids::KBD |
+        str_ids::LI |
+        str_ids::MAIN |
+        str_ids::MARK |
+        str_ids::NAV |
+        str_ids::OL |
+        str_ids::P |
+        str_ids::PRE |
+        str_ids::Q |
+        str_ids::RP |
+        str_ids::RT |
+        str_ids::RUBY |
+        str_ids::S |
+        str_ids::SAMP |
+        str_ids::SECTION |
+        str_ids::SMALL |
+        str_ids::SPAN |
+        str_ids::STRONG |
+        str_ids::SUB |
+        str_ids::SUMMARY |
+        str_ids::SUP |
+        str_ids::TABLE |
+        str_ids::TBODY |
+        str_ids::TD |
+        str_ids::TFOOT |
+        str_ids::TH |
+        str_ids::THEAD |
+        str_ids::TIME |
+        str_ids::TR |
+        str_ids::U |
+        str_ids::UL |
+        str_ids::VAR |
+        str_ids::WBR => TagPolicy::Keep,
+        
+        str_ids::_DOCTYPE |
+        str_ids::APPLET |
+        str_ids::BASE |
+        str_ids::BUTTON |
+        str_ids::EMBED |
+        str_ids::FORM |
+        str_ids::FRAME |
+        str_ids::FRAMESET |
+        str_ids::HEAD |
+        str_ids::IFRAME |
+        str_ids::INPUT |
+        str_ids::LINK |
+        str_ids::MATH |
+        str_ids::META |
+        str_ids::NOSCRIPT |
+        str_ids::OBJECT |
+        str_ids::PARAM |
+        str_ids::SCRIPT |
+        str_ids::SELECT |
+        str_ids::STYLE |
+        str_ids::SVG |
+        str_ids::TEMPLATE |
+        str_ids::TEXTAREA |
+        str_ids::TITLE => TagPolicy::Drop,
+        
+        _ => TagPolicy::Unwrap,
+    }
+}
+
+/// Indicates whether a tag with the given name may have the given attribute.
+/// Event handler attributes such as `onclick` are never allowed.
+fn is_allowed_attr(tag_id: NameID, attr_id: NameID) -> bool {
+    match attr_id {
+        str_ids::CLASS |
+        str_ids::DIR |
+        str_ids::ID |
+        str_ids::LANG |
+        str_ids::TITLE => true,
+        
+        str_ids::HREF |
+        str_ids::REL => tag_id == str_ids::A,
+        
+        str_ids::ALT |
+        str_ids::HEIGHT |
+        str_ids::SRC |
+        str_ids::WIDTH => tag_id == str_ids::IMG,
+        
+        str_ids::CITE => matches!(tag_id, str_ids::BLOCKQUOTE | str_ids::DEL | str_ids::INS | str_ids::Q),
+        str_ids::COLSPAN | str_ids::ROWSPAN => matches!(tag_id, str_ids::TD | str_ids::TH),
+        str_ids::DATETIME => matches!(tag_id, str_ids::DEL | str_ids::INS | str_ids::TIME),
+        str_ids::OPEN => tag_id == str_ids::DETAILS,
+        str_ids::SCOPE => tag_id == str_ids::TH,
+        str_ids::SPAN => matches!(tag_id, str_ids::COL | str_ids::COLGROUP),
+        str_ids::START | str_ids::REVERSED | str_ids::TYPE => tag_id == str_ids::OL,
+        str_ids::VALUE => tag_id == str_ids::LI,
+        
+        _ => false,
+    }
+}
+
+/// Indicates whether the given attribute value is a URL which is safe to link
+/// to. Relative URLs are allowed, and absolute URLs must use the `http`,
+/// `https` or `mailto` scheme; in particular, `javascript:` URLs are not safe.
+fn is_safe_url(url: &str) -> bool {
+    // Browsers ignore whitespace and control characters in URL schemes, e.g.
+    // "java\tscript:" is a `javascript:` URL.
+    let url: String = url.chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect();
+    let scheme_end = url.find([':', '/', '?', '#']);
+    match scheme_end {
+        Some(i) if url[i..].starts_with(':') => {
+            let scheme = url[..i].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        },
+        _ => true,
+    }
+}
+
+impl HTML {
+    /// Returns a copy of this HTML content with any potentially-unsafe tags and
+    /// attributes removed, so that it can be embedded in a page even if it
+    /// comes from an untrusted source. The policy is an allow-list; any tag or
+    /// attribute which is not explicitly allowed is removed.
+    pub(super) fn sanitized(&self) -> HTML {
+        match self {
+            HTML::Tag(tag) => match tag_policy(tag.name_id) {
+                TagPolicy::Keep => tag.sanitized().into(),
+                TagPolicy::Unwrap => tag.content.sanitized(),
+                TagPolicy::Drop => HTML::Empty,
+            },
+            HTML::Sequence(seq) => seq.iter()
+                .map(HTML::sanitized)
+                .collect(),
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Tag {
+    fn sanitized(&self) -> Tag {
+        let attributes = self.attributes.iter()
+            .filter(|&(&k, v)| is_allowed_attr(self.name_id, k) && match (k, v) {
+                (str_ids::CITE | str_ids::HREF | str_ids::SRC, Some(url)) => is_safe_url(url),
+                _ => true,
+            })
+            .map(|(&k, v)| (k, v.clone()))
+            .collect::<AttrMap>();
+        Tag::new_with_attrs(self.name_id, attributes, self.content.sanitized())
+    }
+}
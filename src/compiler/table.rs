@@ -0,0 +1,173 @@
+use crate::errors;
+use crate::parser::Type;
+use crate::utils::{str_ids, NameID};
+use super::base::Compiler;
+use super::html::HTML;
+use super::tag::Tag;
+use super::value::{Value, Int, RcStr};
+
+/// The maximum number of decimal places which numbers in a table can be
+/// written with.
+const MAX_DECIMALS: Int = 20;
+
+/// Parses a string as a number for formatting, if it is written as decimal
+/// digits with an optional sign and decimal point. Strings such as `inf` or
+/// `1e5` are not treated as numbers.
+fn parse_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    let is_number = digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    if is_number { s.parse().ok() } else { None }
+}
+
+/// Formats an integer with the given number of decimal places, which are all
+/// zeroes. Unlike formatting it as a float, this is exact for large integers.
+fn format_int(n: Int, decimals: usize) -> String {
+    if decimals == 0 {
+        n.to_string()
+    } else {
+        format!("{n}.{}", "0".repeat(decimals))
+    }
+}
+
+/// Returns the `text-align` value for a column alignment letter.
+fn alignment(c: char) -> Result<&'static str, errors::RuntimeError> {
+    match c {
+        'l' => Ok("left"),
+        'c' => Ok("center"),
+        'r' => Ok("right"),
+        _ => Err(errors::RuntimeError::InvalidTableAlignment(c)),
+    }
+}
+
+impl <'a> Compiler<'a> {
+    /// Builds a `<table>` from the given data, which is either a CSV string,
+    /// a list of lists, or a list of dictionaries. For a list of dictionaries,
+    /// the first row is the dictionary keys in the order they first occur,
+    /// unless `header_rows` is zero. The first `header_rows` rows are written
+    /// in a `<thead>`, and the rest in a `<tbody>`.
+    ///
+    /// `align` is a string of `l`, `c` or `r` characters, one per column.
+    /// If `decimals` is given, numbers in the body are written with that many
+    /// decimal places, up to a maximum of 20; this includes strings which are
+    /// written as numbers.
+    pub(super) fn table_impl(&mut self, data: Value, header_rows: Int, align: Option<RcStr>, caption: Option<HTML>, decimals: Option<Int>) -> errors::PapyriResult<Tag> {
+        for (name_id, v) in [(str_ids::HEADER_ROWS, Some(header_rows)), (str_ids::DECIMALS, decimals)] {
+            if let Some(v) = v.filter(|&v| v < 0) {
+                let e = errors::RuntimeError::ParamMustBePositive(self.get_name(name_id), v);
+                return Err(e.into());
+            }
+        }
+        if let Some(d) = decimals.filter(|&d| d > MAX_DECIMALS) {
+            let e = errors::RuntimeError::ParamTooLarge(self.get_name(str_ids::DECIMALS), MAX_DECIMALS, d);
+            return Err(e.into());
+        }
+        let align = align.as_deref()
+            .unwrap_or("")
+            .chars()
+            .map(alignment)
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let rows = self.table_rows(data, header_rows > 0)?;
+        let header_rows = rows.len().min(header_rows as usize);
+        let (head, body) = rows.split_at(header_rows);
+        
+        let mut content = Vec::new();
+        if let Some(caption) = caption {
+            content.push(HTML::tag(str_ids::CAPTION, caption));
+        }
+        for (section_id, cell_id, rows) in [(str_ids::THEAD, str_ids::TH, head), (str_ids::TBODY, str_ids::TD, body)] {
+            if rows.is_empty() {
+                continue;
+            }
+            let rows = rows.iter()
+                .map(|row| self.table_row(row, cell_id, &align, decimals.filter(|_| cell_id == str_ids::TD)))
+                .collect();
+            content.push(HTML::tag(section_id, rows));
+        }
+        Ok(Tag::new(str_ids::TABLE, content.into_iter().collect()))
+    }
+    
+    /// Converts the data for a table into a list of rows. If `include_keys` is
+    /// true and the data is a list of dictionaries, the first row is the keys.
+    fn table_rows(&mut self, data: Value, include_keys: bool) -> errors::PapyriResult<Vec<Vec<Value>>> {
+        let rows = match data {
+            Value::Str(s) => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .from_reader(s.as_bytes());
+                let mut rows = Vec::new();
+                for record in reader.records() {
+                    let record = record.map_err(errors::RuntimeError::CsvParseError)?;
+                    rows.push(record.iter().map(Value::from).collect());
+                }
+                rows
+            },
+            Value::List(vs) if matches!(vs.as_ref().first(), Some(Value::Dict(_))) => {
+                let mut keys: Vec<NameID> = Vec::new();
+                let mut dicts = Vec::new();
+                for v in vs.as_ref().iter() {
+                    let Value::Dict(d) = v else {
+                        let e = errors::TypeError::ExpectedWas(Type::Any.dict(), v.get_type());
+                        return Err(e.into());
+                    };
+                    for &k in d.keys() {
+                        if !keys.contains(&k) { keys.push(k); }
+                    }
+                    dicts.push(d.clone());
+                }
+                
+                let header = keys.iter()
+                    .map(|&k| self.get_name(k).into())
+                    .collect();
+                let rows = dicts.iter().map(|d| keys.iter()
+                    .map(|k| d.get(k).cloned().unwrap_or(Value::UNIT))
+                    .collect()
+                );
+                std::iter::once(header)
+                    .filter(|_| include_keys)
+                    .chain(rows)
+                    .collect()
+            },
+            Value::List(vs) => {
+                let mut rows = Vec::new();
+                for v in vs.as_ref().iter() {
+                    let Value::List(row) = v else {
+                        let e = errors::TypeError::ExpectedWas(Type::Any.list(), v.get_type());
+                        return Err(e.into());
+                    };
+                    rows.push(row.as_ref().to_vec());
+                }
+                rows
+            },
+            v => {
+                let e = errors::TypeError::ExpectedWas(Type::Any.list(), v.get_type());
+                return Err(e.into());
+            },
+        };
+        Ok(rows)
+    }
+    
+    fn table_row(&self, row: &[Value], cell_id: NameID, align: &[&str], decimals: Option<Int>) -> HTML {
+        let cells = row.iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let formatted = match (v, decimals) {
+                    (Value::Int(n), Some(d)) => Some(format_int(*n, d as usize)),
+                    (Value::Str(s), Some(d)) => parse_number(s).map(|n| format!("{n:.*}", d as usize)),
+                    _ => None,
+                };
+                let content = formatted.map_or_else(|| self.compile_value(v.clone()), HTML::text);
+                let mut cell = Tag::new(cell_id, content);
+                if let Some(a) = align.get(i) {
+                    cell = cell.str_attr(str_ids::STYLE, &format!("text-align: {a}"));
+                }
+                cell.into()
+            })
+            .collect();
+        HTML::tag(str_ids::TR, cells)
+    }
+}
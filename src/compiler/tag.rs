@@ -13,10 +13,16 @@ use super::value_convert::TryConvert;
 pub(super) type AttrMap = IndexMap<NameID, Option<RcStr>, fxhash::FxBuildHasher>;
 
 #[derive(Debug, Clone)]
+/// An HTML tag, with its attributes and content.
 pub struct Tag {
     pub(super) name_id: NameID,
     pub(super) attributes: AttrMap,
     pub(super) content: HTML,
+    
+    /// The source range where this tag was written, if it was written in
+    /// Papyri source. Tags from other sources, such as parsed HTML, have no
+    /// source range.
+    pub(super) range: Option<SourceRange>,
 }
 
 impl Tag {
@@ -25,7 +31,7 @@ impl Tag {
     }
     
     pub(super) fn new_with_attrs(name_id: NameID, attributes: AttrMap, content: HTML) -> Tag {
-        Tag {name_id, attributes, content}
+        Tag {name_id, attributes, content, range: None}
     }
     
     pub(super) fn str_attr(mut self, k: NameID, v: &str) -> Tag {
@@ -35,6 +41,31 @@ impl Tag {
         }
         self
     }
+    
+    /// Returns the ID of this tag's name. Use `Context::get_name` to get the
+    /// name as a string.
+    pub fn name_id(&self) -> NameID {
+        self.name_id
+    }
+    
+    /// Returns the value of the attribute with the given name ID, if this tag
+    /// has that attribute. The value is `None` for Boolean attributes.
+    pub fn get_attr(&self, name_id: NameID) -> Option<Option<&str>> {
+        self.attributes.get(&name_id)
+            .map(Option::as_deref)
+    }
+    
+    /// Returns an iterator over the (name ID, value) pairs of this tag's
+    /// attributes. The value is `None` for Boolean attributes.
+    pub fn attributes(&self) -> impl Iterator<Item=(NameID, Option<&str>)> {
+        self.attributes.iter()
+            .map(|(&k, v)| (k, v.as_deref()))
+    }
+    
+    /// Returns this tag's content.
+    pub fn content(&self) -> &HTML {
+        &self.content
+    }
 }
 
 impl PartialEq for Tag {
@@ -89,7 +120,9 @@ impl <'a> Compiler<'a> {
         }
         
         let children = self.compile_sequence(&tag.children, taginfo::ContentKind::for_(tag_name_id));
-        Ok(Tag::new_with_attrs(tag_name_id, attrs, children))
+        let mut result = Tag::new_with_attrs(tag_name_id, attrs, children);
+        result.range = Some(self.range_outside_stdlib(tag.range));
+        Ok(result)
     }
     
     fn compile_tag_name(&mut self, name: &ast::TagName) -> errors::Reported<NameID> {
@@ -0,0 +1,107 @@
+use std::rc::Rc;
+
+use crate::errors;
+use crate::utils::text;
+use crate::utils::sourcefile::SourceRange;
+use super::base::Compiler;
+use super::context::Context;
+use super::value::RcStr;
+
+#[derive(Debug, Clone)]
+/// The expected result of a test case declared in a Papyri test file.
+pub enum TestExpectation {
+    /// The test source should compile without errors or warnings, and render
+    /// to exactly this HTML.
+    Output(RcStr),
+    
+    /// The test source should report a diagnostic of this kind, e.g.
+    /// `RuntimeError` or `RuntimeError::Raised`.
+    Error(RcStr),
+}
+
+#[derive(Debug)]
+/// The result of running a test case declared in a Papyri test file, using
+/// the `@test::ok` or `@test::err` functions.
+pub struct TestOutcome {
+    /// The name of the test case.
+    pub name: RcStr,
+    
+    /// The location of the test case declaration, as a string.
+    pub location: String,
+    
+    /// What the test case expected to happen.
+    pub expected: TestExpectation,
+    
+    /// The rendered HTML output, or the diagnostics which were reported, if
+    /// there were any.
+    pub actual: Result<String, String>,
+    
+    /// Indicates whether the test case passed.
+    pub passed: bool,
+}
+
+impl Context {
+    /// Compiles a test source in isolation from the Papyri source file which
+    /// declared it. Relative paths are resolved as if the test source were at
+    /// `at_path`. Returns the rendered HTML if there are no diagnostics, or
+    /// the diagnostics otherwise.
+    fn run_test_source(&mut self, name: &str, at_path: &std::path::Path, src: &str) -> Result<String, errors::Diagnostics> {
+        let reporting_level = errors::ReportingLevel::Warning;
+        let old_diagnostics = std::mem::replace(&mut self.diagnostics, errors::Diagnostics::new(reporting_level));
+        let old_unique_ids = std::mem::take(&mut self.unique_ids);
+        let old_out_files = std::mem::take(&mut self.out_files);
+        let old_tests = std::mem::take(&mut self.tests);
+        
+        let src = self.source_files.load_synthetic_at(&format!("<test {name}>"), at_path, src);
+        let result = self.compile(src);
+        
+        let diagnostics = std::mem::replace(&mut self.diagnostics, old_diagnostics);
+        self.unique_ids = old_unique_ids;
+        self.out_files = old_out_files;
+        self.tests = old_tests;
+        
+        if diagnostics.is_empty() {
+            let mut out = Vec::new();
+            self.render(&result.out, true, &mut out)
+                .unwrap_or_else(|e| errors::ice(&format!("Failed to render test output: {e}")));
+            Ok(String::from_utf8_lossy(&out).into_owned())
+        } else {
+            Err(diagnostics)
+        }
+    }
+}
+
+impl <'a> Compiler<'a> {
+    pub(super) fn native_test_impl(&mut self, name: Option<RcStr>, expected: TestExpectation, src: RcStr, call_range: SourceRange) -> errors::PapyriResult {
+        if self.ctx.tests.is_none() {
+            let e = errors::RuntimeError::TestNotAllowed;
+            return Err(e.into());
+        }
+        
+        let src_file = self.get_source_file(call_range);
+        let (line, col) = src_file.index_to_line_col(call_range.start);
+        let location = format!("File \"{}\", line {line}, col {col}", src_file.path_str);
+        let name = name.unwrap_or_else(|| {
+            let first_line = text::fix_indentation(src.as_ref())
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            Rc::from(format!("line {line}: {first_line}"))
+        });
+        
+        let result = self.ctx.run_test_source(name.as_ref(), &src_file.path, src.as_ref());
+        let passed = match (&expected, &result) {
+            (TestExpectation::Output(expected), Ok(actual)) => expected.as_ref() == actual,
+            (TestExpectation::Error(kind), Err(diagnostics)) => diagnostics.has_any(|d| d.is_kind(kind)),
+            _ => false,
+        };
+        let actual = result.map_err(|diagnostics| format!("{diagnostics:?}"));
+        
+        self.ctx.tests
+            .get_or_insert_with(Vec::new)
+            .push(TestOutcome {name, location, expected, actual, passed});
+        Ok(())
+    }
+}
+
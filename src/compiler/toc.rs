@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use crate::utils::{str_ids, taginfo};
+use super::context::Context;
+use super::html::HTML;
+use super::tag::Tag;
+
+/// The maximum length of a heading ID generated from the heading's text.
+const MAX_ID_LENGTH: usize = 64;
+
+/// A heading in a document, to be listed in its table of contents.
+struct TocEntry {
+    level: u8,
+    id: Rc<str>,
+    text: String,
+}
+
+/// Creates a nested `<ul>` list of links to the given headings. Each heading
+/// is followed by a nested list of the headings below it, up until the next
+/// heading at the same or a higher level.
+fn toc_list(entries: &[&TocEntry]) -> HTML {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < entries.len() {
+        let entry = entries[i];
+        let end = entries[i + 1..].iter()
+            .position(|e| e.level <= entry.level)
+            .map_or(entries.len(), |j| i + 1 + j);
+        
+        let link = Tag::new(str_ids::A, HTML::text(entry.text.as_str()))
+            .str_attr(str_ids::HREF, &format!("#{}", entry.id));
+        let mut item = vec![link.into()];
+        if end > i + 1 {
+            item.push(toc_list(&entries[i + 1..end]));
+        }
+        items.push(HTML::tag(str_ids::LI, item.into_iter().collect()));
+        i = end;
+    }
+    HTML::tag(str_ids::UL, items.into_iter().collect())
+}
+
+impl Context {
+    /// Replaces any table of contents placeholders created by `@toc` in the
+    /// given document. Headings without IDs are given IDs generated from their
+    /// text, and each placeholder becomes a nested list of links to the
+    /// headings. Since this is done after the document has been compiled, a
+    /// table of contents may appear before the headings it lists.
+    /// 
+    /// If there are no placeholders, the document is returned unchanged.
+    pub(super) fn resolve_toc(&mut self, html: HTML) -> HTML {
+        if !html.contains_tag(str_ids::_TOC) {
+            return html;
+        }
+        
+        self.reserve_ids(&html);
+        let mut entries = Vec::new();
+        let html = self.assign_heading_ids(&html, &mut entries);
+        self.replace_toc_placeholders(&html, &entries)
+    }
+    
+    /// Reserves the IDs which occur in the given HTML, so that generated IDs
+    /// don't conflict with them.
+    pub(super) fn reserve_ids(&mut self, html: &HTML) {
+        match html {
+            HTML::Tag(tag) => {
+                if let Some(Some(id)) = tag.get_attr(str_ids::ID) {
+                    self.unique_ids.reserve_id(id);
+                }
+                self.reserve_ids(&tag.content);
+            },
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.reserve_ids(child);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    fn assign_heading_ids(&mut self, html: &HTML, entries: &mut Vec<TocEntry>) -> HTML {
+        match html {
+            HTML::Tag(tag) => if let Some(level) = taginfo::heading_level(tag.name_id) {
+                let mut text = Vec::new();
+                self.render(&tag.content, false, &mut text)
+                    .unwrap_or_else(|_| crate::errors::ice("Failed to render heading text"));
+                let text = String::from_utf8_lossy(&text).trim().to_string();
+                
+                let (html, id) = match tag.get_attr(str_ids::ID) {
+                    Some(Some(id)) => (html.clone(), Rc::from(id)),
+                    _ => {
+                        let id = self.unique_ids.get_unique_id(&text, MAX_ID_LENGTH);
+                        let mut tag = Tag::clone(tag);
+                        tag.attributes.insert(str_ids::ID, Some(id.clone()));
+                        (tag.into(), id)
+                    },
+                };
+                entries.push(TocEntry {level, id, text});
+                html
+            } else {
+                let mut new_tag = Tag::clone(tag);
+                new_tag.content = self.assign_heading_ids(&tag.content, entries);
+                new_tag.into()
+            },
+            HTML::Sequence(seq) => seq.iter()
+                .map(|child| self.assign_heading_ids(child, entries))
+                .collect(),
+            _ => html.clone(),
+        }
+    }
+    
+    fn replace_toc_placeholders(&self, html: &HTML, entries: &[TocEntry]) -> HTML {
+        match html {
+            HTML::Tag(tag) if tag.name_id == str_ids::_TOC => {
+                let max_level: u8 = tag.get_attr(str_ids::MAX_LEVEL)
+                    .flatten()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(6);
+                let entries: Vec<_> = entries.iter()
+                    .filter(|e| e.level <= max_level)
+                    .collect();
+                if entries.is_empty() { HTML::Empty } else { toc_list(&entries) }
+            },
+            HTML::Tag(tag) if tag.content.contains_tag(str_ids::_TOC) => {
+                let mut new_tag = Tag::clone(tag);
+                new_tag.content = self.replace_toc_placeholders(&tag.content, entries);
+                new_tag.into()
+            },
+            HTML::Sequence(seq) => seq.iter()
+                .map(|child| self.replace_toc_placeholders(child, entries))
+                .collect(),
+            _ => html.clone(),
+        }
+    }
+}
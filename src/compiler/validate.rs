@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use crate::errors;
+use crate::utils::{str_ids, taginfo, NameID, StringPool};
+use crate::utils::sourcefile::SourceRange;
+use super::context::Context;
+use super::html::HTML;
+use super::tag::Tag;
+
+/// Checks HTML against the HTML5 content models, collecting a warning for
+/// each violation. Each warning is attributed to the source range of the
+/// innermost tag which was written in Papyri source.
+struct Validator<'a> {
+    string_pool: &'a StringPool,
+    ids: HashSet<String>,
+    warnings: Vec<(errors::Warning, SourceRange)>,
+}
+
+impl <'a> Validator<'a> {
+    fn report(&mut self, e: errors::Warning, range: Option<SourceRange>) {
+        if let Some(range) = range {
+            self.warnings.push((e, range));
+        }
+    }
+    
+    /// Validates the given HTML. `parent` is the nearest enclosing tag, and
+    /// `interactive` is the nearest enclosing `<a>` or `<button>` tag, if any.
+    fn visit(&mut self, html: &HTML, parent: Option<NameID>, interactive: Option<NameID>, range: Option<SourceRange>) {
+        match html {
+            HTML::Tag(tag) => self.visit_tag(tag, parent, interactive, range),
+            HTML::Sequence(seq) => {
+                for child in seq.iter() {
+                    self.visit(child, parent, interactive, range);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    fn visit_tag(&mut self, tag: &Tag, parent: Option<NameID>, interactive: Option<NameID>, range: Option<SourceRange>) {
+        let name_id = tag.name_id;
+        if name_id == str_ids::_DOCTYPE { return; }
+        let range = tag.range.or(range);
+        if matches!(name_id, str_ids::_LABEL | str_ids::_REF) {
+            self.visit(&tag.content, parent, interactive, range);
+            return;
+        }
+        let name = self.string_pool.get(name_id);
+        
+        if let Some(parents) = taginfo::required_parents(name_id) {
+            if !parent.is_some_and(|p| parents.contains(&p)) {
+                let expected = parents.iter()
+                    .map(|&p| format!("'{}'", self.string_pool.get(p)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.report(errors::Warning::TagRequiresParent(name.clone(), expected.into()), range);
+            }
+        }
+        if let Some(p) = parent.filter(|&p| taginfo::requires_phrasing_content(p) && taginfo::is_flow_only(name_id)) {
+            self.report(errors::Warning::TagNotAllowedIn(name.clone(), self.string_pool.get(p)), range);
+        }
+        if let Some(a) = interactive.filter(|_| taginfo::is_interactive(name_id)) {
+            self.report(errors::Warning::TagNotAllowedIn(name.clone(), self.string_pool.get(a)), range);
+        }
+        
+        let tag_attrs = taginfo::tag_attrs(&name);
+        for (&k, v) in tag.attributes.iter() {
+            if let (str_ids::ID, Some(id)) = (k, v) {
+                if !self.ids.insert(id.to_string()) {
+                    self.report(errors::Warning::DuplicateID(id.clone()), range);
+                }
+            }
+            
+            let attr = self.string_pool.get(k)
+                .replace('_', "-");
+            if matches!(tag_attrs, Some(attrs) if !attrs.contains(&attr.as_str()) && !taginfo::is_global_attr(&attr)) {
+                self.report(errors::Warning::UnknownAttribute(attr.into(), name.clone()), range);
+            }
+        }
+        
+        // SVG, MathML and template contents have their own content models
+        if matches!(name_id, str_ids::MATH | str_ids::SVG | str_ids::TEMPLATE) { return; }
+        
+        let interactive = interactive.or(Some(name_id).filter(|&n| n == str_ids::A || n == str_ids::BUTTON));
+        self.visit(&tag.content, Some(name_id), interactive, range);
+    }
+}
+
+impl Context {
+    /// Checks the given HTML against the HTML5 content models, and reports a
+    /// warning for each problem found, such as `<li>` outside of a list, a
+    /// block-level tag inside `<p>`, nested links, duplicate IDs or unknown
+    /// attributes. Problems in HTML which was not written in Papyri source,
+    /// such as fetched HTML, are only reported if it is inside a tag which was.
+    pub fn validate_html(&mut self, html: &HTML) {
+        let mut validator = Validator {
+            string_pool: &self.string_pool,
+            ids: HashSet::new(),
+            warnings: Vec::new(),
+        };
+        validator.visit(html, None, None, None);
+        
+        for (e, range) in validator.warnings {
+            let src = self.source_files.get(range.src_id);
+            self.diagnostics.report_static(e, src, range);
+        }
+    }
+}
@@ -152,6 +152,7 @@ impl <'a> Compiler<'a> {
     /// Evaluates an AST node to a value. Returns `None` if a compilation error
     /// occurs.
     pub(super) fn evaluate_node(&mut self, node: &Expr, type_hint: &Type) -> errors::Reported<Value> {
+        self.take_step(node.range())?;
         let v = match node {
             Expr::Unit(..) => Value::UNIT,
             &Expr::Bool(b, ..) => b.into(),
@@ -8,8 +8,15 @@ use super::html::HTML;
 use super::regex_value::RcRegex;
 use super::value::{Value, Dict, Int, RcStr, RcDict, List};
 
-pub(super) trait TryConvert where Self: Sized {
+/// A Rust type which Papyri values may be converted to, e.g. in order to get
+/// the arguments of a native function.
+pub trait TryConvert where Self: Sized {
+    /// The Papyri type of values which can be converted to this Rust type.
     fn as_type() -> Type;
+    
+    /// Converts a value to this Rust type, or returns `Err` if the value is
+    /// not of the right type.
+    #[allow(clippy::result_unit_err)]
     fn try_convert(value: Value) -> Result<Self, ()>;
 }
 impl Value {
@@ -14,7 +14,7 @@ pub use warning::Warning;
 
 mod sink;
 mod sink_base;
-pub use sink_base::{StackTrace, ReportingLevel, DiagSourceRange};
+pub use sink_base::{StackTrace, ReportingLevel, Severity, Diagnostic, DiagSourceRange};
 pub use sink::{Diagnostics, PapyriError, AlreadyReported};
 
 /// A result type for which `Err` means a diagnostic must be reported by the
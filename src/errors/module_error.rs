@@ -7,6 +7,17 @@ pub enum ModuleError {
     PreviousError(Box<std::path::Path>),
 }
 
+impl ModuleError {
+    /// Returns the name of this error's variant, such as `IOError`.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            ModuleError::IOError(..) => "IOError",
+            ModuleError::CircularImport(..) => "CircularImport",
+            ModuleError::PreviousError(..) => "PreviousError",
+        }
+    }
+}
+
 impl std::fmt::Display for ModuleError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let path = match self {
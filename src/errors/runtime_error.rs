@@ -24,6 +24,7 @@ pub enum RuntimeError {
     ParamExistsButNotImplicit(std::rc::Rc<str>),
     ParamMultipleValues(std::rc::Rc<str>),
     ParamMustBePositive(std::rc::Rc<str>, i64),
+    ParamTooLarge(std::rc::Rc<str>, i64, i64),
     
     RegexSyntaxError(regex::Error),
     RegexMixedGroupKinds,
@@ -31,13 +32,48 @@ pub enum RuntimeError {
     
     Raised(std::rc::Rc<str>),
     NoMatchingBranch,
+    CallDepthExceeded(usize),
+    StepLimitExceeded(u64),
+    TimeLimitExceeded(std::time::Duration),
     IndexOutOfRange(i64, usize),
     ParseIntError(std::num::ParseIntError),
     FileReadError(std::rc::Rc<str>, std::io::Error),
     PathNotInOutDir(std::rc::Rc<str>),
+    PathNotInRoot(std::rc::Rc<str>),
     WriteFileNotAllowed,
+    InvalidRenderMode(std::rc::Rc<str>),
+    LabelNotElement,
+    LabelKindUnknown(std::rc::Rc<str>),
+    UnknownCitationKey(std::rc::Rc<str>),
+    InvalidCitationStyle(std::rc::Rc<str>),
+    InvalidTableAlignment(char),
+    TestNotAllowed,
+    NotAllowed(&'static str),
     HtmlParseError(String),
+    XmlParseError(String),
     NetworkError(reqwest::Error),
+    FetchOffline(std::rc::Rc<str>),
+    FetchCacheError(std::io::Error),
+    FetchInvalidMethod(std::rc::Rc<str>),
+    FetchUnexpectedStatus(std::rc::Rc<str>, i64, u16),
+    JsonParseError(serde_json::Error),
+    TomlParseError(toml::de::Error),
+    CsvParseError(csv::Error),
+    BibliographyParseError(String),
+    DataKeyCollision(std::rc::Rc<str>),
+    MathSyntaxError(String),
+}
+
+impl NameError {
+    /// Returns the name of this error's variant, such as `NoSuchVariable`.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            NameError::NoSuchVariable(..) => "NoSuchVariable",
+            NameError::NoSuchParameter(..) => "NoSuchParameter",
+            NameError::NoSuchAttribute(..) => "NoSuchAttribute",
+            NameError::InvalidTag(..) => "InvalidTag",
+        }
+    }
 }
 
 impl std::fmt::Display for NameError {
@@ -51,6 +87,56 @@ impl std::fmt::Display for NameError {
     }
 }
 
+impl RuntimeError {
+    /// Returns the name of this error's variant, such as `AttrMultipleValues`.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            RuntimeError::AttrMultipleValues(..) => "AttrMultipleValues",
+            RuntimeError::ParamMissing(..) => "ParamMissing",
+            RuntimeError::ParamMissingImplicit(..) => "ParamMissingImplicit",
+            RuntimeError::ParamExistsButNotImplicit(..) => "ParamExistsButNotImplicit",
+            RuntimeError::ParamMultipleValues(..) => "ParamMultipleValues",
+            RuntimeError::ParamMustBePositive(..) => "ParamMustBePositive",
+            RuntimeError::ParamTooLarge(..) => "ParamTooLarge",
+            RuntimeError::RegexSyntaxError(..) => "RegexSyntaxError",
+            RuntimeError::RegexMixedGroupKinds => "RegexMixedGroupKinds",
+            RuntimeError::RegexInvalidGroupName(..) => "RegexInvalidGroupName",
+            RuntimeError::Raised(..) => "Raised",
+            RuntimeError::NoMatchingBranch => "NoMatchingBranch",
+            RuntimeError::CallDepthExceeded(..) => "CallDepthExceeded",
+            RuntimeError::StepLimitExceeded(..) => "StepLimitExceeded",
+            RuntimeError::TimeLimitExceeded(..) => "TimeLimitExceeded",
+            RuntimeError::IndexOutOfRange(..) => "IndexOutOfRange",
+            RuntimeError::ParseIntError(..) => "ParseIntError",
+            RuntimeError::FileReadError(..) => "FileReadError",
+            RuntimeError::PathNotInOutDir(..) => "PathNotInOutDir",
+            RuntimeError::PathNotInRoot(..) => "PathNotInRoot",
+            RuntimeError::WriteFileNotAllowed => "WriteFileNotAllowed",
+            RuntimeError::InvalidRenderMode(..) => "InvalidRenderMode",
+            RuntimeError::LabelNotElement => "LabelNotElement",
+            RuntimeError::LabelKindUnknown(..) => "LabelKindUnknown",
+            RuntimeError::UnknownCitationKey(..) => "UnknownCitationKey",
+            RuntimeError::InvalidCitationStyle(..) => "InvalidCitationStyle",
+            RuntimeError::InvalidTableAlignment(..) => "InvalidTableAlignment",
+            RuntimeError::TestNotAllowed => "TestNotAllowed",
+            RuntimeError::NotAllowed(..) => "NotAllowed",
+            RuntimeError::HtmlParseError(..) => "HtmlParseError",
+            RuntimeError::XmlParseError(..) => "XmlParseError",
+            RuntimeError::NetworkError(..) => "NetworkError",
+            RuntimeError::FetchOffline(..) => "FetchOffline",
+            RuntimeError::FetchCacheError(..) => "FetchCacheError",
+            RuntimeError::FetchInvalidMethod(..) => "FetchInvalidMethod",
+            RuntimeError::FetchUnexpectedStatus(..) => "FetchUnexpectedStatus",
+            RuntimeError::JsonParseError(..) => "JsonParseError",
+            RuntimeError::TomlParseError(..) => "TomlParseError",
+            RuntimeError::CsvParseError(..) => "CsvParseError",
+            RuntimeError::BibliographyParseError(..) => "BibliographyParseError",
+            RuntimeError::DataKeyCollision(..) => "DataKeyCollision",
+            RuntimeError::MathSyntaxError(..) => "MathSyntaxError",
+        }
+    }
+}
+
 impl std::fmt::Display for RuntimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -60,18 +146,42 @@ impl std::fmt::Display for RuntimeError {
             RuntimeError::ParamExistsButNotImplicit(name) => write!(f, "required implicit parameter '{name}'; this name exists but is not declared implicit"),
             RuntimeError::ParamMultipleValues(name) => write!(f, "received multiple named arguments for parameter '{name}'"),
             RuntimeError::ParamMustBePositive(name, was) => write!(f, "parameter '{name}' must be positive (was {was})"),
+            RuntimeError::ParamTooLarge(name, max, was) => write!(f, "parameter '{name}' must be at most {max} (was {was})"),
             RuntimeError::RegexSyntaxError(e) => write!(f, "regex syntax error ({e})"),
             RuntimeError::RegexMixedGroupKinds => f.write_str("regex cannot have both named and unnamed capture groups"),
             RuntimeError::RegexInvalidGroupName(name) => write!(f, "regex group name '{name}' is not a valid identifier"),
             RuntimeError::Raised(msg) => f.write_str(msg),
             RuntimeError::NoMatchingBranch => f.write_str("no matching branch in @match"),
+            RuntimeError::CallDepthExceeded(depth) => write!(f, "maximum call depth of {depth} exceeded"),
+            RuntimeError::StepLimitExceeded(steps) => write!(f, "maximum of {steps} evaluation steps exceeded"),
+            RuntimeError::TimeLimitExceeded(timeout) => write!(f, "time limit of {timeout:?} exceeded"),
             RuntimeError::IndexOutOfRange(i, len) => write!(f, "index out of bounds (index {i}, length {len})"),
             RuntimeError::ParseIntError(e) => write!(f, "failed to parse int ({e})"),
             RuntimeError::FileReadError(path, e) => write!(f, "failed to read file \"{path}\" ({e})"),
             RuntimeError::PathNotInOutDir(path) => write!(f, "path \"{path}\" is not within output directory"),
+            RuntimeError::PathNotInRoot(path) => write!(f, "path \"{path}\" is not within the allowed root directory"),
             RuntimeError::WriteFileNotAllowed => f.write_str("no output directory for '@file::write'; use '--out'"),
+            RuntimeError::InvalidRenderMode(mode) => write!(f, "invalid render mode '{mode}'; expected 'html', 'text', 'markdown' or 'raw'"),
+            RuntimeError::LabelNotElement => f.write_str("'@label' content must be a single element"),
+            RuntimeError::LabelKindUnknown(name) => write!(f, "cannot infer what kind of label '{name}' tag has; use 'kind' parameter"),
+            RuntimeError::UnknownCitationKey(key) => write!(f, "no bibliography entry with key '{key}'; use '@bib::load' first"),
+            RuntimeError::InvalidCitationStyle(style) => write!(f, "invalid citation style '{style}'; expected 'author-year' or 'numeric'"),
+            RuntimeError::InvalidTableAlignment(c) => write!(f, "invalid column alignment '{c}'; expected 'l', 'c' or 'r'"),
+            RuntimeError::TestNotAllowed => f.write_str("'@test' functions can only be used in test files; use 'papyri test'"),
+            RuntimeError::NotAllowed(name) => write!(f, "'{name}' is not allowed in this context"),
             RuntimeError::HtmlParseError(e) => write!(f, "failed to parse HTML ({e})"),
+            RuntimeError::XmlParseError(e) => write!(f, "failed to parse XML ({e})"),
             RuntimeError::NetworkError(e) => write!(f, "network error ({e})"),
+            RuntimeError::FetchOffline(url) => write!(f, "\"{url}\" is not in the fetch cache, and network requests are disabled"),
+            RuntimeError::FetchCacheError(e) => write!(f, "failed to write fetch cache ({e})"),
+            RuntimeError::FetchInvalidMethod(method) => write!(f, "invalid HTTP method '{method}'"),
+            RuntimeError::FetchUnexpectedStatus(url, expected, was) => write!(f, "\"{url}\" responded with status {was} (expected {expected})"),
+            RuntimeError::JsonParseError(e) => write!(f, "failed to parse JSON ({e})"),
+            RuntimeError::TomlParseError(e) => write!(f, "failed to parse TOML ({e})"),
+            RuntimeError::CsvParseError(e) => write!(f, "failed to parse CSV ({e})"),
+            RuntimeError::BibliographyParseError(e) => write!(f, "failed to parse bibliography ({e})"),
+            RuntimeError::DataKeyCollision(name) => write!(f, "multiple keys map to the same name '{name}'"),
+            RuntimeError::MathSyntaxError(e) => write!(f, "invalid TeX math ({e})"),
         }
     }
 }
@@ -34,6 +34,29 @@ impl PapyriError {
             PapyriError::AlreadyReported => Severity::Debug,
         }
     }
+    
+    /// Returns the name of this diagnostic's kind, in the form
+    /// `Category::Variant`, e.g. `RuntimeError::Raised`.
+    pub fn kind_name(&self) -> String {
+        let (category, variant) = match self {
+            PapyriError::ModuleError(e) => ("ModuleError", e.variant_name()),
+            PapyriError::NameError(e) => ("NameError", e.variant_name()),
+            PapyriError::RuntimeError(e) => ("RuntimeError", e.variant_name()),
+            PapyriError::SyntaxError(e) => ("SyntaxError", e.variant_name()),
+            PapyriError::TypeError(e) => ("TypeError", e.variant_name()),
+            PapyriError::Warning(e) => ("Warning", e.variant_name()),
+            PapyriError::AlreadyReported => return "AlreadyReported".to_string(),
+        };
+        format!("{category}::{variant}")
+    }
+    
+    /// Indicates whether this diagnostic is of the given kind. The kind is
+    /// either a category such as `RuntimeError`, or a category and variant
+    /// such as `RuntimeError::Raised`.
+    pub fn is_kind(&self, kind: &str) -> bool {
+        let kind_name = self.kind_name();
+        kind_name == kind || kind_name.split("::").next() == Some(kind)
+    }
 }
 
 /// Represents that a failure occurred but a diagnostic was already reported
@@ -64,6 +64,27 @@ impl DiagSourceRange {
             in_func: Some(Box::from(func_name)),
         }
     }
+    
+    fn same_position(&self, other: &DiagSourceRange) -> bool {
+        Rc::ptr_eq(&self.src, &other.src) && self.start == other.start
+    }
+    
+    /// Returns the path of the source file, as a string.
+    pub fn path_str(&self) -> &str {
+        &self.src.path_str
+    }
+    
+    /// Returns the line and column numbers of the start of this range. Both
+    /// are numbered from 1.
+    pub fn line_col(&self) -> (u32, u32) {
+        self.src.index_to_line_col(self.start)
+    }
+    
+    /// Returns the name of the function which was called at this position,
+    /// if this range is part of a stack trace.
+    pub fn in_func(&self) -> Option<&str> {
+        self.in_func.as_deref()
+    }
 }
 
 /// Represents a stack trace, which is associated with a diagnostic.
@@ -74,14 +95,41 @@ impl DiagSourceRange {
 /// in order from least recent to most recent.
 pub type StackTrace = Box<[DiagSourceRange]>;
 
+/// The maximum number of consecutive identical lines to show in a stack trace.
+const MAX_REPEATED_TRACE_LINES: usize = 3;
+
 /// Holds information about an error or warning which has occurred during
 /// compilation of a Papyri source file.
-struct Diagnostic<T: std::fmt::Display> {
+pub struct Diagnostic<T: std::fmt::Display> {
+    severity: Severity,
     msg: T,
     range: DiagSourceRange,
     trace: Option<StackTrace>,
 }
 
+impl <T: std::fmt::Display> Diagnostic<T> {
+    /// Returns the severity of this diagnostic.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+    
+    /// Returns the error or warning which this diagnostic reports.
+    pub fn msg(&self) -> &T {
+        &self.msg
+    }
+    
+    /// Returns the position in the source file where the diagnostic occurred.
+    pub fn range(&self) -> &DiagSourceRange {
+        &self.range
+    }
+    
+    /// Returns the stack trace associated with this diagnostic, in order from
+    /// least recent to most recent call. The trace is empty if there is none.
+    pub fn trace(&self) -> &[DiagSourceRange] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+}
+
 impl <T: std::fmt::Display> std::fmt::Display for Diagnostic<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         fn write_trace_line(f: &mut std::fmt::Formatter<'_>, range: &DiagSourceRange, func_name: Option<&str>) -> std::fmt::Result {
@@ -93,14 +141,35 @@ impl <T: std::fmt::Display> std::fmt::Display for Diagnostic<T> {
             writeln!(f)
         }
         
+        fn write_repeated(f: &mut std::fmt::Formatter<'_>, repeats: usize) -> std::fmt::Result {
+            if repeats >= MAX_REPEATED_TRACE_LINES {
+                let n = repeats + 1 - MAX_REPEATED_TRACE_LINES;
+                writeln!(f, "    [Previous line repeated {n} more time{}]", text::pluralise(n as u32))?;
+            }
+            Ok(())
+        }
+        
         if let Some(trace) = &self.trace {
             writeln!(f, "Traceback (most recent call last):")?;
+            
+            // deep recursion makes long traces, so repeated lines are collapsed
+            let mut prev: Option<(&DiagSourceRange, Option<&str>)> = None;
+            let mut repeats = 0;
             let mut in_func = None;
-            for call_range in trace.iter() {
-                write_trace_line(f, call_range, in_func)?;
+            for call_range in trace.iter().chain(std::iter::once(&self.range)) {
+                if matches!(prev, Some((r, g)) if r.same_position(call_range) && g == in_func) {
+                    repeats += 1;
+                } else {
+                    write_repeated(f, repeats)?;
+                    repeats = 0;
+                }
+                if repeats < MAX_REPEATED_TRACE_LINES {
+                    write_trace_line(f, call_range, in_func)?;
+                }
+                prev = Some((call_range, in_func));
                 in_func = call_range.in_func.as_ref().map(Box::as_ref);
             }
-            write_trace_line(f, &self.range, in_func)?;
+            write_repeated(f, repeats)?;
             writeln!(f, "{}", self.msg)?;
         } else {
             writeln!(f, "{}", self.msg)?;
@@ -140,6 +209,12 @@ impl <T: std::fmt::Display> DiagnosticSink<T> {
         self.v.iter().any(|d| predicate(&d.msg))
     }
     
+    /// Returns an iterator over the diagnostics in this collection, in the
+    /// order they were reported.
+    pub fn iter(&self) -> impl Iterator<Item=&Diagnostic<T>> {
+        self.v.iter()
+    }
+    
     /// Clears the collection, making it empty.
     pub fn clear(&mut self) {
         self.v.clear();
@@ -147,6 +222,12 @@ impl <T: std::fmt::Display> DiagnosticSink<T> {
         self.num_warnings = 0;
     }
     
+    /// Moves the diagnostics out of this collection into a new collection,
+    /// leaving this collection empty.
+    pub fn take(&mut self) -> DiagnosticSink<T> {
+        std::mem::replace(self, DiagnosticSink::new(self.reporting_level))
+    }
+    
     /// Prints the diagnostics in this collection to stderr.
     pub fn print_to_stderr(&self) {
         for diag in self.v.iter() {
@@ -185,7 +266,7 @@ impl <T: std::fmt::Display> DiagnosticSink<T> {
         }
         if self.reporting_level.should_report(severity) {
             let range = DiagSourceRange::at(source_file, range);
-            self.v.push(Diagnostic {msg, range, trace});
+            self.v.push(Diagnostic {severity, msg, range, trace});
         }
     }
 }
@@ -67,6 +67,68 @@ pub enum SyntaxError {
     PatternIndexAccess,
 }
 
+impl SyntaxError {
+    /// Returns the name of this error's variant, such as `TokenExpected`.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            SyntaxError::TokenExpected(..) => "TokenExpected",
+            SyntaxError::TokenExpectedDoctype => "TokenExpectedDoctype",
+            SyntaxError::TokenExpectedWas(..) => "TokenExpectedWas",
+            SyntaxError::TokenExpectedWasEOF(..) => "TokenExpectedWasEOF",
+            SyntaxError::TokenUnexpected(..) => "TokenUnexpected",
+            SyntaxError::TokenUnmatched(..) => "TokenUnmatched",
+            SyntaxError::TokenInvalidNumber(..) => "TokenInvalidNumber",
+            SyntaxError::TokenEntityMissingSemicolon => "TokenEntityMissingSemicolon",
+            SyntaxError::TokenInvalidEntity => "TokenInvalidEntity",
+            SyntaxError::TokenInvalidEscape => "TokenInvalidEscape",
+            SyntaxError::TokenInvalidPrimitiveType => "TokenInvalidPrimitiveType",
+            SyntaxError::TokenInvalidGroupType => "TokenInvalidGroupType",
+            SyntaxError::TokenVerbatimMultilineNotEnoughBackticks => "TokenVerbatimMultilineNotEnoughBackticks",
+            SyntaxError::TokenVerbatimTooManyBackticks => "TokenVerbatimTooManyBackticks",
+            SyntaxError::TokenVerbatimEOF => "TokenVerbatimEOF",
+            SyntaxError::ExpectedExpr => "ExpectedExpr",
+            SyntaxError::UnexpectedEOF => "UnexpectedEOF",
+            SyntaxError::TagCloseMalformed => "TagCloseMalformed",
+            SyntaxError::TagUnmatchedOpen => "TagUnmatchedOpen",
+            SyntaxError::TagDuplicateAttr(..) => "TagDuplicateAttr",
+            SyntaxError::SpreadPositionalNotAllowed => "SpreadPositionalNotAllowed",
+            SyntaxError::SpreadNamedNotAllowed => "SpreadNamedNotAllowed",
+            SyntaxError::AnonymousFunctionNotAllowed => "AnonymousFunctionNotAllowed",
+            SyntaxError::ParamDuplicateName(..) => "ParamDuplicateName",
+            SyntaxError::ParamPositionalAfterNamed => "ParamPositionalAfterNamed",
+            SyntaxError::ParamRequiredAfterOptional => "ParamRequiredAfterOptional",
+            SyntaxError::ParamDefaultImplicit => "ParamDefaultImplicit",
+            SyntaxError::ParamPositionalImplicit => "ParamPositionalImplicit",
+            SyntaxError::ParamSpreadDefault => "ParamSpreadDefault",
+            SyntaxError::ParamSpreadImplicit => "ParamSpreadImplicit",
+            SyntaxError::ParamMultipleSpread => "ParamMultipleSpread",
+            SyntaxError::ParamAfterSpread => "ParamAfterSpread",
+            SyntaxError::ParamPositionalSpreadNoUnderscore => "ParamPositionalSpreadNoUnderscore",
+            SyntaxError::ParamNamedSpreadUnderscore => "ParamNamedSpreadUnderscore",
+            SyntaxError::ParamContentSpread => "ParamContentSpread",
+            SyntaxError::ParamContentDefault => "ParamContentDefault",
+            SyntaxError::ArgDuplicateName(..) => "ArgDuplicateName",
+            SyntaxError::ArgNamedNotAllowed => "ArgNamedNotAllowed",
+            SyntaxError::ArgPositionalAfterNamed => "ArgPositionalAfterNamed",
+            SyntaxError::ArgSpreadNamed => "ArgSpreadNamed",
+            SyntaxError::ArgNamedUnderscore => "ArgNamedUnderscore",
+            SyntaxError::DeclMissingArgs => "DeclMissingArgs",
+            SyntaxError::DeclPositionalArg => "DeclPositionalArg",
+            SyntaxError::LetInLiteral => "LetInLiteral",
+            SyntaxError::ExportNotAllowed => "ExportNotAllowed",
+            SyntaxError::PatternBareName => "PatternBareName",
+            SyntaxError::PatternMultipleSpreads => "PatternMultipleSpreads",
+            SyntaxError::PatternNamedUnderscore => "PatternNamedUnderscore",
+            SyntaxError::PatternNamedAfterSpread => "PatternNamedAfterSpread",
+            SyntaxError::PatternDuplicateName(..) => "PatternDuplicateName",
+            SyntaxError::PatternIncorrectCloseTag => "PatternIncorrectCloseTag",
+            SyntaxError::PatternCannotMatchHTML => "PatternCannotMatchHTML",
+            SyntaxError::PatternAttrAccess => "PatternAttrAccess",
+            SyntaxError::PatternIndexAccess => "PatternIndexAccess",
+        }
+    }
+}
+
 impl std::fmt::Display for SyntaxError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
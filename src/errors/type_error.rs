@@ -14,6 +14,25 @@ pub enum TypeError {
     ContentAlreadyBound,
     SortKeyInvalid(Type),
     SortKeyHeterogeneous,
+    NotJsonSerializable(Type),
+}
+
+impl TypeError {
+    /// Returns the name of this error's variant, such as `ExpectedWas`.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            TypeError::ExpectedWas(..) => "ExpectedWas",
+            TypeError::TooManyPositionalArgs(..) => "TooManyPositionalArgs",
+            TypeError::NotEnoughPositionalArgs(..) => "NotEnoughPositionalArgs",
+            TypeError::TagNotAllowed(..) => "TagNotAllowed",
+            TypeError::ParagraphBreakNotAllowed => "ParagraphBreakNotAllowed",
+            TypeError::NoContentAllowed => "NoContentAllowed",
+            TypeError::ContentAlreadyBound => "ContentAlreadyBound",
+            TypeError::SortKeyInvalid(..) => "SortKeyInvalid",
+            TypeError::SortKeyHeterogeneous => "SortKeyHeterogeneous",
+            TypeError::NotJsonSerializable(..) => "NotJsonSerializable",
+        }
+    }
 }
 
 impl std::fmt::Display for TypeError {
@@ -28,6 +47,7 @@ impl std::fmt::Display for TypeError {
             TypeError::ContentAlreadyBound => f.write_str("this function's contents have already been bound (expected none)"),
             TypeError::SortKeyInvalid(was) => write!(f, "sort key must be str or int, was {was}"),
             TypeError::SortKeyHeterogeneous => f.write_str("sort key must be homogeneous, not a mix of str and int"),
+            TypeError::NotJsonSerializable(was) => write!(f, "value of type '{was}' cannot be encoded as JSON"),
         }
     }
 }
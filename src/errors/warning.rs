@@ -12,6 +12,51 @@ pub enum Warning {
     HighlightNotEnabled,
     HighlightLanguageUnknown(std::rc::Rc<str>),
     BrokenLink(std::rc::Rc<str>),
+    BrokenLinkFragment(std::rc::Rc<str>),
+    TagRequiresParent(std::rc::Rc<str>, std::rc::Rc<str>),
+    TagNotAllowedIn(std::rc::Rc<str>, std::rc::Rc<str>),
+    DuplicateID(std::rc::Rc<str>),
+    UnknownAttribute(std::rc::Rc<str>, std::rc::Rc<str>),
+    MissingAltText,
+    HeadingLevelSkipped(u8, u8),
+    EmptyLinkText,
+    TableWithoutHeaders,
+    MissingFormLabel(std::rc::Rc<str>),
+    MissingLang,
+    UnknownLabel(std::rc::Rc<str>),
+    DuplicateLabel(std::rc::Rc<str>),
+    CiteContentIsKey(std::rc::Rc<str>),
+}
+
+impl Warning {
+    /// Returns the name of this warning's variant, such as `RedundantOptionType`.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Warning::RedundantOptionType => "RedundantOptionType",
+            Warning::NameAlreadyDeclared(..) => "NameAlreadyDeclared",
+            Warning::PatternNameAlreadyBound(..) => "PatternNameAlreadyBound",
+            Warning::NameAlreadyExported(..) => "NameAlreadyExported",
+            Warning::InlineHighlightEnumerate => "InlineHighlightEnumerate",
+            Warning::InlineHighlightMultiline => "InlineHighlightMultiline",
+            Warning::HighlightNotEnabled => "HighlightNotEnabled",
+            Warning::HighlightLanguageUnknown(..) => "HighlightLanguageUnknown",
+            Warning::BrokenLink(..) => "BrokenLink",
+            Warning::BrokenLinkFragment(..) => "BrokenLinkFragment",
+            Warning::TagRequiresParent(..) => "TagRequiresParent",
+            Warning::TagNotAllowedIn(..) => "TagNotAllowedIn",
+            Warning::DuplicateID(..) => "DuplicateID",
+            Warning::UnknownAttribute(..) => "UnknownAttribute",
+            Warning::MissingAltText => "MissingAltText",
+            Warning::HeadingLevelSkipped(..) => "HeadingLevelSkipped",
+            Warning::EmptyLinkText => "EmptyLinkText",
+            Warning::TableWithoutHeaders => "TableWithoutHeaders",
+            Warning::MissingFormLabel(..) => "MissingFormLabel",
+            Warning::MissingLang => "MissingLang",
+            Warning::UnknownLabel(..) => "UnknownLabel",
+            Warning::DuplicateLabel(..) => "DuplicateLabel",
+            Warning::CiteContentIsKey(..) => "CiteContentIsKey",
+        }
+    }
 }
 
 impl std::fmt::Display for Warning {
@@ -26,6 +71,20 @@ impl std::fmt::Display for Warning {
             Warning::HighlightNotEnabled => f.write_str("syntax highlighting is not enabled in this build"),
             Warning::HighlightLanguageUnknown(language) => write!(f, "no syntax highlighter found for language \"{language}\""),
             Warning::BrokenLink(path) => write!(f, "linked file does not exist at \"{path}\""),
+            Warning::TagRequiresParent(name, parents) => write!(f, "'{name}' tag must be directly inside one of {parents}"),
+            Warning::TagNotAllowedIn(name, parent) => write!(f, "'{name}' tag not allowed inside '{parent}'"),
+            Warning::DuplicateID(id) => write!(f, "duplicate id \"{id}\""),
+            Warning::UnknownAttribute(attr, name) => write!(f, "unknown attribute '{attr}' for '{name}' tag"),
+            Warning::MissingAltText => f.write_str("image has no 'alt' attribute; use alt=\"\" if it is decorative"),
+            Warning::HeadingLevelSkipped(from, to) => write!(f, "heading level skipped from 'h{from}' to 'h{to}'"),
+            Warning::EmptyLinkText => f.write_str("link has no text"),
+            Warning::TableWithoutHeaders => f.write_str("table has no header cells"),
+            Warning::MissingFormLabel(name) => write!(f, "'{name}' tag has no label"),
+            Warning::MissingLang => f.write_str("'html' tag has no 'lang' attribute"),
+            Warning::BrokenLinkFragment(url) => write!(f, "linked page has no element with the fragment ID in \"{url}\""),
+            Warning::UnknownLabel(name) => write!(f, "no such label '{name}'"),
+            Warning::DuplicateLabel(name) => write!(f, "label '{name}' already defined"),
+            Warning::CiteContentIsKey(key) => write!(f, "'{key}' is a citation key, but is cited as a title; use @cite(`{key}`) to cite it"),
         }
     }
 }
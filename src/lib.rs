@@ -13,18 +13,6 @@ pub mod utils;
 /// errors or warnings occur during compilation, the diagnostics are returned
 /// instead.
 pub fn compile_str(src: &str) -> Result<String, errors::Diagnostics> {
-    let mut ctx = compiler::Context::new(errors::ReportingLevel::Warning, None);
-    let src = ctx.source_files.load_synthetic("<string>", src);
-    let result = ctx.compile(src);
-    
-    if ctx.diagnostics.is_empty() {
-        let mut out = Vec::new();
-        ctx.render(&result.out, true, &mut out)
-            .unwrap();
-        
-        let out = String::from_utf8(out).unwrap();
-        Ok(out)
-    } else {
-        Err(ctx.diagnostics)
-    }
+    compiler::Context::new(errors::ReportingLevel::Warning, None)
+        .compile_str(src)
 }
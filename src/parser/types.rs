@@ -56,19 +56,19 @@ pub enum Type {
 
 impl Type {
     /// Creates a representation of a `dict` type.
-    pub(crate) fn dict(self) -> Type {
+    pub fn dict(self) -> Type {
         Type::Dict(Box::new(self))
     }
     
     /// Creates a representation of a `list` type.
-    pub(crate) fn list(self) -> Type {
+    pub fn list(self) -> Type {
         Type::List(Box::new(self))
     }
     
     /// Creates a representation of an optional type. The representation is
     /// normalised by simplifying `T?` to `T` whenever the unit value is
     /// already assignable to `T`.
-    pub(crate) fn option(self) -> Type {
+    pub fn option(self) -> Type {
         if self.unit_is_assignable() {
             self
         } else {
@@ -25,47 +25,83 @@ macro_rules! const_strs {
 
 const_strs!(
     ANONYMOUS = "<anonymous>",
+    _BIBLIOGRAPHY = "!BIBLIOGRAPHY",
+    _CITE = "!CITE",
     _DOCTYPE = "!DOCTYPE",
+    _FOOTNOTE = "!FOOTNOTE",
+    _FOOTNOTES = "!FOOTNOTES",
+    _LABEL = "!LABEL",
+    _REF = "!REF",
+    _TOC = "!TOC",
     A = "a",
+    ABBR = "abbr",
+    ACCENT = "accent",
+    ACCENTUNDER = "accentunder",
     ADD = "add",
     ADDRESS = "address",
+    ALIGN = "align",
     ALL = "all",
+    ALT = "alt",
+    ALTTEXT = "alttext",
     AND = "and",
     ANY = "any",
+    APPLET = "applet",
     AREA = "area",
     ARGS = "args",
     ARTICLE = "article",
     ASIDE = "aside",
+    ASSET = "asset",
+    AUDIO = "audio",
     B = "b",
     BASE = "base",
+    BDI = "bdi",
+    BDO = "bdo",
+    BIB = "bib",
+    BIBLIOGRAPHY = "bibliography",
     BIND = "bind",
     BLOCKQUOTE = "blockquote",
     BODY = "body",
     BOOL = "bool",
     BR = "br",
+    BUTTON = "button",
     CANVAS = "canvas",
     CAPTION = "caption",
+    CITE = "cite",
     CLASS = "class",
     CODE = "code",
     CODE_BLOCK = "code_block",
     COL = "col",
     COLGROUP = "colgroup",
+    COLSPAN = "colspan",
+    COLUMNALIGN = "columnalign",
     COMMAND = "command",
     COMPILE = "compile",
     CONTAINS = "contains",
     COUNT = "count",
+    DATA = "data",
+    DATALIST = "datalist",
     DATA_LINE_NO = "data_line_no",
     DATA_PAREN_NO = "data_paren_no",
+    DATETIME = "datetime",
     DD = "dd",
+    DECIMALS = "decimals",
+    DEL = "del",
     DETAILS = "details",
+    DFN = "dfn",
     DICT = "dict",
+    DIR = "dir",
+    DISPLAY = "display",
     DIV = "div",
     DL = "dl",
     DT = "dt",
+    EM = "em",
     EMBED = "embed",
+    ENCODE = "encode",
     ENDS_WITH = "ends_with",
     ENUMERATE = "enumerate",
+    ERR = "err",
     ESCAPE_HTML = "escape_html",
+    EXPECTED = "expected",
     FETCH = "fetch",
     FIELDSET = "fieldset",
     FIGCAPTION = "figcaption",
@@ -77,7 +113,10 @@ const_strs!(
     FIND_ALL = "find_all",
     FLAT = "flat",
     FOOTER = "footer",
+    FOOTNOTES = "footnotes",
     FORM = "form",
+    FRAME = "frame",
+    FRAMESET = "frameset",
     FROM = "from",
     FUNCTION = "function",
     GET = "get",
@@ -87,85 +126,171 @@ const_strs!(
     H4 = "h4",
     H5 = "h5",
     H6 = "h6",
+    HASH = "hash",
     HEAD = "head",
     HEADER = "header",
+    HEADERS = "headers",
+    HEADER_ROWS = "header_rows",
+    HEIGHT = "height",
     HGROUP = "hgroup",
     HR = "hr",
     HREF = "href",
     HTML = "html",
     HTML_NODES = "html_nodes",
     I = "i",
+    ID = "id",
+    IFRAME = "iframe",
     IMG = "img",
     IMPORT = "import",
     INCLUDE = "include",
     INPUT = "input",
+    INS = "ins",
     INT = "int",
     IS_EMPTY = "is_empty",
     IS_WHITESPACE = "is_whitespace",
     ITEMS = "items",
     JOIN = "join",
+    JSON = "json",
+    KBD = "kbd",
     KEY = "key",
     KEYGEN = "keygen",
     KEYS = "keys",
+    KIND = "kind",
     KWARGS = "kwargs",
+    LABEL = "label",
+    LANG = "lang",
     LANGUAGE = "language",
+    LEGEND = "legend",
     LEN = "len",
     LI = "li",
+    LINETHICKNESS = "linethickness",
     LINK = "link",
     LIST = "list",
+    LOAD = "load",
     LOWER = "lower",
     MAIN = "main",
     MAP = "map",
+    MARK = "mark",
+    MATH = "math",
+    MATHVARIANT = "mathvariant",
     MAX_LENGTH = "max_length",
+    MAX_LEVEL = "max_level",
     MENU = "menu",
     MENUITEM = "menuitem",
     META = "meta",
+    METHOD = "method",
+    MFRAC = "mfrac",
+    MI = "mi",
+    MN = "mn",
+    MO = "mo",
+    MODE = "mode",
+    MOVER = "mover",
+    MROOT = "mroot",
+    MROW = "mrow",
+    MSPACE = "mspace",
+    MSQRT = "msqrt",
+    MSUB = "msub",
+    MSUBSUP = "msubsup",
+    MSUP = "msup",
+    MTABLE = "mtable",
+    MTD = "mtd",
+    MTEXT = "mtext",
+    MTR = "mtr",
+    MUNDER = "munder",
+    MUNDEROVER = "munderover",
     NAME = "name",
     NAV = "nav",
     NEGATE = "negate",
     NEW = "new",
     NOSCRIPT = "noscript",
+    NOTE = "note",
+    OBJECT = "object",
+    OK = "ok",
     OL = "ol",
+    OPEN = "open",
+    OPTGROUP = "optgroup",
+    OPTION = "option",
     OR = "or",
     P = "p",
     PARAM = "param",
     PARSE = "parse",
     PATH = "path",
+    PICTURE = "picture",
     PRE = "pre",
+    PRETTY = "pretty",
+    Q = "q",
     RAISE = "raise",
     RAW = "raw",
     READ = "read",
+    READ_CSV = "read_csv",
+    READ_HTML = "read_html",
+    READ_JSON = "read_json",
+    READ_TOML = "read_toml",
+    REF = "ref",
     REGEX = "regex",
+    REL = "rel",
+    RENDER = "render",
     REVERSED = "reversed",
+    ROWSPAN = "rowspan",
+    RP = "rp",
+    RT = "rt",
+    RUBY = "ruby",
+    S = "s",
+    SAMP = "samp",
+    SANITIZE = "sanitize",
+    SCOPE = "scope",
     SCRIPT = "script",
     SECTION = "section",
+    SELECT = "select",
     SEP = "sep",
     SLICE = "slice",
+    SMALL = "small",
     SORTED = "sorted",
     SOURCE = "source",
     SPAN = "span",
     SPLIT = "split",
+    SRC = "src",
+    START = "start",
     STARTS_WITH = "starts_with",
+    STATUS = "status",
     STR = "str",
+    STRONG = "strong",
     STYLE = "style",
+    SUB = "sub",
+    SUMMARY = "summary",
+    SUP = "sup",
+    SVG = "svg",
     TABLE = "table",
     TAG_NAME = "tag_name",
     TBODY = "tbody",
     TD = "td",
     TEMPLATE = "template",
     TEST = "test",
+    TEXT = "text",
+    TEXTAREA = "textarea",
     TFOOT = "tfoot",
     TH = "th",
     THEAD = "thead",
+    TIME = "time",
     TITLE = "title",
+    TOC = "toc",
     TR = "tr",
     TRACK = "track",
     TRIM = "trim",
+    TYPE = "type",
+    U = "u",
     UL = "ul",
     UNIQUE_ID = "unique_id",
     UPPER = "upper",
+    URL = "url",
+    VALUE = "value",
     VALUES = "values",
+    VAR = "var",
     VIDEO = "video",
     WBR = "wbr",
+    WIDTH = "width",
     WRITE = "write",
+    WRITE_JSON = "write_json",
+    XML = "xml",
+    XMLNS = "xmlns",
 );
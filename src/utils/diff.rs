@@ -0,0 +1,177 @@
+//! This module contains a simple line-based diff algorithm, used to report
+//! differences between expected and actual output.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// The maximum number of entries in the table used to find the longest common
+/// subsequence. Beyond this, changed regions are reported as a deletion of all
+/// of the old lines followed by an insertion of all of the new lines.
+const MAX_TABLE_SIZE: usize = 1 << 22;
+
+/// Computes an edit script transforming `old` into `new`. Lines in common at
+/// the start and end are kept; the lines in between are compared using the
+/// longest common subsequence, if they are not too many.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    
+    let mut edits = vec![Edit::Keep; prefix];
+    let (old_mid, new_mid) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+    if (old_mid.len() + 1).saturating_mul(new_mid.len() + 1) <= MAX_TABLE_SIZE {
+        edits.extend(lcs_edit_script(old_mid, new_mid));
+    } else {
+        edits.resize(edits.len() + old_mid.len(), Edit::Delete);
+        edits.resize(edits.len() + new_mid.len(), Edit::Insert);
+    }
+    edits.resize(edits.len() + suffix, Edit::Keep);
+    edits
+}
+
+/// Computes a shortest edit script transforming `old` into `new`, using the
+/// longest common subsequence of lines. This takes time and memory
+/// proportional to the product of the numbers of lines.
+fn lcs_edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let (n, m) = (old.len(), new.len());
+    
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    
+    let mut edits = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            edits.push(Edit::Keep);
+            i += 1; j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] > lcs[i + 1][j]) {
+            edits.push(Edit::Insert);
+            j += 1;
+        } else {
+            edits.push(Edit::Delete);
+            i += 1;
+        }
+    }
+    edits
+}
+
+/// Returns a unified diff of the lines in `old` and `new`, with the given
+/// number of lines of context around each change. The result is empty if the
+/// two strings have the same lines.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let edits = edit_script(&old, &new);
+    
+    // positions in `old` and `new` before each edit
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for &edit in edits.iter() {
+        positions.push((i, j));
+        match edit {
+            Edit::Keep => { i += 1; j += 1; },
+            Edit::Delete => { i += 1; },
+            Edit::Insert => { j += 1; },
+        }
+    }
+    positions.push((i, j));
+    
+    let mut out = String::new();
+    let mut k = 0;
+    while k < edits.len() {
+        if edits[k] == Edit::Keep { k += 1; continue; }
+        
+        // extend the hunk while changes are within `2 * context` of each other
+        let start = k.saturating_sub(context);
+        let mut end = k;
+        let mut keeps = 0;
+        while end < edits.len() && keeps <= 2 * context {
+            if edits[end] == Edit::Keep { keeps += 1; } else { keeps = 0; }
+            end += 1;
+        }
+        end -= keeps.saturating_sub(context);
+        
+        let (old_start, new_start) = positions[start];
+        let (old_end, new_end) = positions[end];
+        out += &format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_end),
+            hunk_range(new_start, new_end),
+        );
+        for (&edit, &(i, j)) in edits[start..end].iter().zip(positions[start..end].iter()) {
+            match edit {
+                Edit::Keep => out += &format!(" {}\n", old[i]),
+                Edit::Delete => out += &format!("-{}\n", old[i]),
+                Edit::Insert => out += &format!("+{}\n", new[j]),
+            }
+        }
+        k = end;
+    }
+    out
+}
+
+/// Formats a range of lines for a hunk header. By convention, an empty range
+/// is numbered by the line before it.
+fn hunk_range(start: usize, end: usize) -> String {
+    let len = end - start;
+    format!("{},{len}", if len == 0 { start } else { start + 1 })
+}
+
+/// Inserts line breaks between adjacent HTML tags, so that a diff of rendered
+/// HTML shows which tags changed instead of a single long line.
+pub fn split_html_lines(html: &str) -> String {
+    html.replace("><", ">\n<")
+}
+
+#[cfg(test)]
+mod test {
+    use super::unified_diff;
+    
+    #[test]
+    fn no_changes() {
+        assert_eq!(unified_diff("a\nb\nc", "a\nb\nc", 3), "");
+    }
+    
+    #[test]
+    fn one_change() {
+        assert_eq!(
+            unified_diff("a\nb\nc\nd\ne", "a\nb\nx\nd\ne", 1),
+            "@@ -2,3 +2,3 @@\n b\n-c\n+x\n d\n",
+        );
+    }
+    
+    #[test]
+    fn large_change() {
+        let old = "x\n".repeat(3000);
+        let new = "y\n".repeat(3000);
+        let diff = unified_diff(&format!("a\n{old}b"), &format!("a\n{new}b"), 1);
+        assert!(diff.starts_with("@@ -1,3002 +1,3002 @@\n a\n-x\n"));
+        assert!(diff.ends_with("+y\n b\n"));
+    }
+    
+    #[test]
+    fn separate_hunks() {
+        assert_eq!(
+            unified_diff("a\nb\nc\nd\ne\nf\ng", "x\nb\nc\nd\ne\nf\ny", 1),
+            "@@ -1,2 +1,2 @@\n-a\n+x\n b\n@@ -6,2 +6,2 @@\n f\n-g\n+y\n",
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! This module contains the `FileSystem` trait, through which the compiler
+//! accesses files, along with implementations for the operating system's file
+//! system and for an in-memory file system.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use normalize_path::NormalizePath;
+
+use crate::errors;
+use super::relpath;
+
+/// A file system which the Papyri compiler can read source files and data
+/// files from, and write output files to.
+pub trait FileSystem {
+    /// Reads the contents of the file at the given path as a string.
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+    
+    /// Reads the contents of the file at the given path as bytes.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    
+    /// Returns the canonical, absolute form of the given path. Fails if no
+    /// file or directory exists at that path.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+    
+    /// Returns the paths of all files contained recursively in the given
+    /// directory, relative to that directory. Errors for individual entries
+    /// are reported to the `on_error` callback, and those entries are skipped.
+    fn list_files(&self, path: &Path, on_error: &mut dyn FnMut(&Path, io::Error)) -> io::Result<Vec<PathBuf>>;
+    
+    /// Writes the given contents to the file at the given path, creating its
+    /// parent directories if necessary.
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+}
+
+/// The operating system's file system.
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+    
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+    
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+    
+    fn list_files(&self, path: &Path, on_error: &mut dyn FnMut(&Path, io::Error)) -> io::Result<Vec<PathBuf>> {
+        let canonical_path = std::fs::canonicalize(path)?;
+        
+        let mut entry_to_path = |entry: Result<walkdir::DirEntry, walkdir::Error>| -> Option<PathBuf> {
+            match entry {
+                Ok(entry) => {
+                    if entry.file_type().is_dir() { return None; }
+                    let entry_path = entry.path()
+                        .canonicalize()
+                        .map_err(|e| on_error(entry.path(), e))
+                        .ok()?;
+                    relpath::make_relative(&canonical_path, &entry_path)
+                },
+                Err(e) => {
+                    let path = e.path()
+                        .unwrap_or(&canonical_path)
+                        .to_path_buf();
+                    let io_err = e.into_io_error()
+                        .unwrap_or_else(|| errors::ice("Not an IO error"));
+                    on_error(&path, io_err);
+                    None
+                },
+            }
+        };
+        
+        Ok(walkdir::WalkDir::new(path)
+            .into_iter()
+            .filter_map(&mut entry_to_path)
+            .collect())
+    }
+    
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
+}
+
+/// An in-memory file system. Relative paths are resolved from the root
+/// directory, and directories exist implicitly whenever they contain a file.
+#[derive(Default)]
+pub struct MemoryFileSystem {
+    files: RefCell<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryFileSystem {
+    /// Creates a new, empty in-memory file system.
+    pub fn new() -> MemoryFileSystem {
+        MemoryFileSystem::default()
+    }
+    
+    /// Adds a file to this file system, replacing any existing file at the
+    /// same path.
+    pub fn add_file<P: AsRef<Path>, T: Into<Vec<u8>>>(&self, path: P, contents: T) {
+        self.files.borrow_mut()
+            .insert(Self::normalize(path.as_ref()), contents.into());
+    }
+    
+    /// Returns the contents of the file at the given path, if there is one.
+    pub fn get_file<P: AsRef<Path>>(&self, path: P) -> Option<Vec<u8>> {
+        self.files.borrow()
+            .get(&Self::normalize(path.as_ref()))
+            .cloned()
+    }
+    
+    /// Returns the paths of all files in this file system, in sorted order.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.borrow()
+            .keys()
+            .cloned()
+            .collect()
+    }
+    
+    fn normalize(path: &Path) -> PathBuf {
+        Path::new("/")
+            .join(path)
+            .normalize()
+    }
+    
+    fn is_dir(&self, path: &Path) -> bool {
+        self.files.borrow()
+            .keys()
+            .any(|p| p != path && p.starts_with(path))
+    }
+    
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no such file or directory \"{}\"", path.to_string_lossy()),
+        )
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.get_file(path)
+            .ok_or_else(|| Self::not_found(path))
+    }
+    
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let p = Self::normalize(path);
+        if self.files.borrow().contains_key(&p) || self.is_dir(&p) {
+            Ok(p)
+        } else {
+            Err(Self::not_found(path))
+        }
+    }
+    
+    fn list_files(&self, path: &Path, _on_error: &mut dyn FnMut(&Path, io::Error)) -> io::Result<Vec<PathBuf>> {
+        let base = self.canonicalize(path)?;
+        Ok(self.files.borrow()
+            .keys()
+            .filter_map(|p| p.strip_prefix(&base).ok())
+            .map(Path::to_path_buf)
+            .collect())
+    }
+    
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.add_file(path, contents);
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@
 //! used in multiple other modules.
 
 mod const_strs;
+pub mod diff;
+pub mod filesystem;
 mod outfiles;
 pub mod relpath;
 mod sliceref;
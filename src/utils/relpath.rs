@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use normalize_path::NormalizePath;
 
 use crate::errors;
+use super::filesystem::FileSystem;
 
 /// Converts the given path `rel` into a normalised relative path using the
 /// given `base` path. Returns `None` if the resulting path would not be a
@@ -22,37 +23,13 @@ pub fn make_relative(base: &Path, rel: &Path) -> Option<PathBuf> {
 /// 
 /// Any filesystem errors which occur are reported to the `on_error` callback;
 /// `None` is returned if the given base path is erroneous.
-pub fn find_papyri_source_files_in_dir(path: &Path, mut on_error: impl FnMut(&Path, std::io::Error)) -> errors::Reported<Vec<PathBuf>> {
-    let canonical_path = std::fs::canonicalize(path)
+pub fn find_papyri_source_files_in_dir(fs: &dyn FileSystem, path: &Path, mut on_error: impl FnMut(&Path, std::io::Error)) -> errors::Reported<Vec<PathBuf>> {
+    let mut paths: Vec<_> = fs.list_files(path, &mut on_error)
         .map_err(|e| {
             on_error(path, e);
             errors::AlreadyReported
-        })?;
-    
-    let entry_to_path = |entry: Result<walkdir::DirEntry, walkdir::Error>| -> Option<PathBuf> {
-        match entry {
-            Ok(entry) => {
-                let entry_path = entry.path()
-                    .canonicalize()
-                    .map_err(|e| on_error(entry.path(), e))
-                    .ok()?;
-                make_relative(&canonical_path, &entry_path)
-            },
-            Err(e) => {
-                let path = e.path()
-                    .unwrap_or(&canonical_path)
-                    .to_path_buf();
-                let io_err = e.into_io_error()
-                    .unwrap_or_else(|| errors::ice("Not an IO error"));
-                on_error(&path, io_err);
-                None
-            },
-        }
-    };
-    
-    let mut paths: Vec<_> = walkdir::WalkDir::new(path)
+        })?
         .into_iter()
-        .filter_map(entry_to_path)
         .filter(|p| super::sourcefile::is_papyri_file(p))
         .collect();
     
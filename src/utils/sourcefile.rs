@@ -1,11 +1,12 @@
 //! This module contains declarations for Papyri source files and spans.
 
-use std::{fs, path};
+use std::path;
 use std::rc::Rc;
 use nonmax::NonMaxU32;
 use once_cell::unsync::OnceCell;
 
 use crate::errors;
+use super::filesystem::FileSystem;
 
 /// Determines whether the given file path has the `.papyri` extension.
 pub fn is_papyri_file(path: &path::Path) -> bool {
@@ -19,6 +20,13 @@ pub fn is_papyri_library(path: &path::Path) -> bool {
     s.ends_with(".lib.papyri")
 }
 
+/// Determines whether the given file path appears to be a Papyri test file,
+/// i.e. a file with the `.test.papyri` extension.
+pub fn is_papyri_test(path: &path::Path) -> bool {
+    let s = path.to_string_lossy().to_ascii_lowercase();
+    s.ends_with(".test.papyri")
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct SourceFileID(NonMaxU32);
 
@@ -171,9 +179,23 @@ impl SourceFileCache {
         s
     }
     
-    /// Loads a source file from the given path.
-    pub(crate) fn load_from_path(&mut self, path: &path::Path) -> std::io::Result<Rc<SourceFile>> {
-        let src = fs::read_to_string(path)?;
+    /// Creates a new synthetic source file which does not exist on the file
+    /// system, but which resolves relative paths as if it were located at the
+    /// given path. This is used for test cases declared in Papyri test files.
+    pub(crate) fn load_synthetic_at(&mut self, path_str: &str, path: &path::Path, src: &str) -> Rc<SourceFile> {
+        let s = Rc::new(SourceFile::new(
+            self.next_id(),
+            Box::from(path),
+            Box::from(path_str),
+            Box::from(src),
+        ));
+        self.files.push(s.clone());
+        s
+    }
+    
+    /// Loads a source file from the given path in the given file system.
+    pub(crate) fn load_from_path(&mut self, fs: &dyn FileSystem, path: &path::Path) -> std::io::Result<Rc<SourceFile>> {
+        let src = fs.read_to_string(path)?;
         let s = Rc::new(SourceFile::new(
             self.next_id(),
             Box::from(path),
@@ -8,6 +8,7 @@ pub(crate) fn is_self_closing(name_id: NameID) -> bool {
     // https://developer.mozilla.org/en-US/docs/Glossary/Void_element
     matches!(
         name_id,
+        str_ids::_BIBLIOGRAPHY |
         str_ids::_DOCTYPE |
         str_ids::AREA |
         str_ids::BASE |
@@ -34,10 +35,14 @@ pub(crate) fn is_self_closing(name_id: NameID) -> bool {
 pub(crate) fn is_block(name_id: NameID) -> bool {
     // https://developer.mozilla.org/en-US/docs/Web/HTML/Block-level_elements
     // Extras:
-    // - !DOCTYPE, base, body, canvas, head, html, link, menu, meta, script, video
+    // - !BIBLIOGRAPHY, !DOCTYPE, !FOOTNOTES, !LABEL, !TOC, base, body, canvas, head, html, link, menu, meta, script, video
     matches!(
         name_id,
+        str_ids::_BIBLIOGRAPHY |
         str_ids::_DOCTYPE |
+        str_ids::_FOOTNOTES |
+        str_ids::_LABEL |
+        str_ids::_TOC |
         str_ids::ADDRESS |
         str_ids::ARTICLE |
         str_ids::ASIDE |
@@ -87,6 +92,19 @@ pub(crate) fn is_block(name_id: NameID) -> bool {
     )
 }
 
+/// Returns the heading level of a tag with this name, if it is a heading.
+pub(crate) fn heading_level(name_id: NameID) -> Option<u8> {
+    match name_id {
+        str_ids::H1 => Some(1),
+        str_ids::H2 => Some(2),
+        str_ids::H3 => Some(3),
+        str_ids::H4 => Some(4),
+        str_ids::H5 => Some(5),
+        str_ids::H6 => Some(6),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 /// Specifies what contents/children an HTML tag can have.
 pub(crate) enum ContentKind {
@@ -216,3 +234,209 @@ impl ContentKind {
         }
     }
 }
+
+/// Returns the names of the tags which a tag with this name must be a child
+/// of, according to the HTML5 content models, or `None` if it may appear in
+/// any parent.
+pub(crate) fn required_parents(name_id: NameID) -> Option<&'static [NameID]> {
+    // https://html.spec.whatwg.org/multipage/indices.html#elements-3
+    match name_id {
+        str_ids::LI => Some(&[str_ids::MENU, str_ids::OL, str_ids::UL]),
+        str_ids::DD | str_ids::DT => Some(&[str_ids::DIV, str_ids::DL]),
+        str_ids::TR => Some(&[str_ids::TABLE, str_ids::TBODY, str_ids::TFOOT, str_ids::THEAD]),
+        str_ids::TD | str_ids::TH => Some(&[str_ids::TR]),
+        str_ids::CAPTION |
+        str_ids::COLGROUP |
+        str_ids::TBODY |
+        str_ids::TFOOT |
+        str_ids::THEAD => Some(&[str_ids::TABLE]),
+        str_ids::COL => Some(&[str_ids::COLGROUP]),
+        str_ids::FIGCAPTION => Some(&[str_ids::FIGURE]),
+        str_ids::LEGEND => Some(&[str_ids::FIELDSET]),
+        str_ids::SUMMARY => Some(&[str_ids::DETAILS]),
+        str_ids::OPTION => Some(&[str_ids::DATALIST, str_ids::OPTGROUP, str_ids::SELECT]),
+        str_ids::OPTGROUP => Some(&[str_ids::SELECT]),
+        str_ids::RP | str_ids::RT => Some(&[str_ids::RUBY]),
+        str_ids::SOURCE => Some(&[str_ids::AUDIO, str_ids::PICTURE, str_ids::VIDEO]),
+        str_ids::TRACK => Some(&[str_ids::AUDIO, str_ids::VIDEO]),
+        str_ids::PARAM => Some(&[str_ids::OBJECT]),
+        _ => None,
+    }
+}
+
+/// Indicates whether a tag with this name may only contain phrasing content,
+/// i.e. text and inline tags.
+pub(crate) fn requires_phrasing_content(name_id: NameID) -> bool {
+    matches!(
+        name_id,
+        str_ids::ABBR |
+        str_ids::B |
+        str_ids::BDI |
+        str_ids::BDO |
+        str_ids::BUTTON |
+        str_ids::CITE |
+        str_ids::CODE |
+        str_ids::DFN |
+        str_ids::EM |
+        str_ids::H1 |
+        str_ids::H2 |
+        str_ids::H3 |
+        str_ids::H4 |
+        str_ids::H5 |
+        str_ids::H6 |
+        str_ids::I |
+        str_ids::KBD |
+        str_ids::LABEL |
+        str_ids::LEGEND |
+        str_ids::MARK |
+        str_ids::P |
+        str_ids::PRE |
+        str_ids::Q |
+        str_ids::S |
+        str_ids::SAMP |
+        str_ids::SMALL |
+        str_ids::SPAN |
+        str_ids::STRONG |
+        str_ids::SUB |
+        str_ids::SUP |
+        str_ids::TIME |
+        str_ids::U |
+        str_ids::VAR
+    )
+}
+
+/// Indicates whether `name_id` is the id of a HTML tag name which is not
+/// phrasing content, so it cannot appear where only phrasing content is
+/// allowed. Unlike `is_block`, this excludes tags such as `<img>` which are
+/// not wrapped in paragraphs, but are still phrasing content.
+pub(crate) fn is_flow_only(name_id: NameID) -> bool {
+    is_block(name_id) && !matches!(
+        name_id,
+        str_ids::CANVAS |
+        str_ids::IMG |
+        str_ids::LINK |
+        str_ids::META |
+        str_ids::SCRIPT |
+        str_ids::TEMPLATE |
+        str_ids::VIDEO
+    )
+}
+
+/// Indicates whether `name_id` is the id of an interactive HTML tag name,
+/// which cannot be nested inside `<a>` or `<button>`.
+pub(crate) fn is_interactive(name_id: NameID) -> bool {
+    matches!(
+        name_id,
+        str_ids::A |
+        str_ids::BUTTON |
+        str_ids::DETAILS |
+        str_ids::EMBED |
+        str_ids::IFRAME |
+        str_ids::INPUT |
+        str_ids::LABEL |
+        str_ids::SELECT |
+        str_ids::TEXTAREA
+    )
+}
+
+/// Indicates whether the given attribute name is allowed on any HTML tag.
+/// Attribute names should be written with `-`, not `_`.
+pub(crate) fn is_global_attr(attr: &str) -> bool {
+    // https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes
+    attr.starts_with("aria-") || attr.starts_with("data-") || attr.starts_with("on") || matches!(
+        attr,
+        "accesskey" |
+        "autocapitalize" |
+        "autofocus" |
+        "class" |
+        "contenteditable" |
+        "dir" |
+        "draggable" |
+        "enterkeyhint" |
+        "hidden" |
+        "id" |
+        "inert" |
+        "inputmode" |
+        "is" |
+        "itemid" |
+        "itemprop" |
+        "itemref" |
+        "itemscope" |
+        "itemtype" |
+        "lang" |
+        "nonce" |
+        "part" |
+        "popover" |
+        "role" |
+        "slot" |
+        "spellcheck" |
+        "style" |
+        "tabindex" |
+        "title" |
+        "translate" |
+        "xmlns"
+    )
+}
+
+/// Returns the names of the attributes specific to the HTML tag with the
+/// given name, not including global attributes, or `None` if the tag is not a
+/// known HTML tag. Attribute names are written with `-`, not `_`.
+pub(crate) fn tag_attrs(tag: &str) -> Option<&'static [&'static str]> {
+    // https://developer.mozilla.org/en-US/docs/Web/HTML/Attributes
+    let attrs: &[&str] = match tag {
+        "abbr" | "address" | "article" | "aside" | "b" | "bdi" | "bdo" |
+        "body" | "br" | "caption" | "cite" | "code" | "datalist" | "dd" |
+        "dfn" | "div" | "dl" | "dt" | "em" | "figcaption" | "figure" |
+        "footer" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "head" |
+        "header" | "hgroup" | "hr" | "i" | "kbd" | "legend" | "main" |
+        "mark" | "menu" | "nav" | "noscript" | "p" | "picture" | "pre" |
+        "rp" | "rt" | "ruby" | "s" | "samp" | "search" | "section" |
+        "small" | "span" | "strong" | "sub" | "summary" | "sup" | "table" | "tbody" |
+        "tfoot" | "thead" | "title" | "tr" | "u" | "ul" | "var" |
+        "wbr" => &[],
+        
+        "a" => &["download", "href", "hreflang", "ping", "referrerpolicy", "rel", "target", "type"],
+        "area" => &["alt", "coords", "download", "href", "ping", "referrerpolicy", "rel", "shape", "target"],
+        "audio" => &["autoplay", "controls", "crossorigin", "loop", "muted", "preload", "src"],
+        "base" => &["href", "target"],
+        "blockquote" | "q" => &["cite"],
+        "button" => &["disabled", "form", "formaction", "formenctype", "formmethod", "formnovalidate", "formtarget", "name", "popovertarget", "popovertargetaction", "type", "value"],
+        "canvas" => &["height", "width"],
+        "col" | "colgroup" => &["span"],
+        "data" => &["value"],
+        "del" | "ins" => &["cite", "datetime"],
+        "details" => &["name", "open"],
+        "dialog" => &["open"],
+        "embed" => &["height", "src", "type", "width"],
+        "fieldset" => &["disabled", "form", "name"],
+        "form" => &["accept-charset", "action", "autocomplete", "enctype", "method", "name", "novalidate", "rel", "target"],
+        "html" => &["manifest", "version"],
+        "iframe" => &["allow", "allowfullscreen", "height", "loading", "name", "referrerpolicy", "sandbox", "src", "srcdoc", "width"],
+        "img" => &["alt", "crossorigin", "decoding", "fetchpriority", "height", "ismap", "loading", "referrerpolicy", "sizes", "src", "srcset", "usemap", "width"],
+        "input" => &["accept", "alt", "autocomplete", "capture", "checked", "dirname", "disabled", "form", "formaction", "formenctype", "formmethod", "formnovalidate", "formtarget", "height", "list", "max", "maxlength", "min", "minlength", "multiple", "name", "pattern", "placeholder", "readonly", "required", "size", "src", "step", "type", "value", "width"],
+        "label" | "output" => &["for", "form", "name"],
+        "li" => &["value"],
+        "link" => &["as", "blocking", "crossorigin", "disabled", "fetchpriority", "href", "hreflang", "imagesizes", "imagesrcset", "integrity", "media", "referrerpolicy", "rel", "sizes", "type"],
+        "map" | "slot" => &["name"],
+        "meta" => &["charset", "content", "http-equiv", "media", "name"],
+        "meter" => &["form", "high", "low", "max", "min", "optimum", "value"],
+        "object" => &["data", "form", "height", "name", "type", "width"],
+        "ol" => &["reversed", "start", "type"],
+        "optgroup" => &["disabled", "label"],
+        "option" => &["disabled", "label", "selected", "value"],
+        "progress" => &["max", "value"],
+        "script" => &["async", "blocking", "crossorigin", "defer", "fetchpriority", "integrity", "nomodule", "referrerpolicy", "src", "type"],
+        "select" => &["autocomplete", "disabled", "form", "multiple", "name", "required", "size"],
+        "source" => &["height", "media", "sizes", "src", "srcset", "type", "width"],
+        "style" => &["blocking", "media"],
+        "td" => &["colspan", "headers", "rowspan"],
+        "template" => &["shadowrootmode"],
+        "textarea" => &["autocomplete", "cols", "dirname", "disabled", "form", "maxlength", "minlength", "name", "placeholder", "readonly", "required", "rows", "wrap"],
+        "th" => &["abbr", "colspan", "headers", "rowspan", "scope"],
+        "time" => &["datetime"],
+        "track" => &["default", "kind", "label", "src", "srclang"],
+        "video" => &["autoplay", "controls", "crossorigin", "height", "loop", "muted", "playsinline", "poster", "preload", "src", "width"],
+        _ => return None,
+    };
+    Some(attrs)
+}
@@ -169,7 +169,9 @@ impl UniqueIDGenerator {
         let id: Rc<str> = Rc::from(id);
         if self.ids_used.insert(id.clone()) { return id; }
         
-        let mut id_base = id_base;
+        // the suffixed ID is based on the normalised ID, so that it is also a
+        // valid lowercase identifier
+        let mut id_base: &str = &id;
         if id_base.len() + 2 > max_len && max_len >= 3 {
             id_base = &id_base[..max_len - 2];
         }
@@ -191,4 +193,11 @@ impl UniqueIDGenerator {
         self.ids_used.insert(id.clone());
         id
     }
+    
+    /// Marks the given identifier as used, so that it will not be returned by
+    /// `get_unique_id` until this generator is cleared. This is used for
+    /// identifiers which were written explicitly, rather than generated.
+    pub fn reserve_id(&mut self, id: &str) {
+        self.ids_used.insert(Rc::from(id));
+    }
 }
@@ -0,0 +1,67 @@
+mod common;
+
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{ReportingLevel, Warning};
+
+/// Compiles the source and checks the output for accessibility problems,
+/// returning the warnings as strings.
+fn lint(src: &str) -> Vec<String> {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    let doc = ctx.compile_document(src);
+    assert!(doc.diagnostics.is_empty(), "{:?}", doc.diagnostics);
+    ctx.lint_accessibility(&doc.html);
+    common::warnings(&ctx.diagnostics.take())
+}
+
+fn warning(w: Warning) -> Vec<String> {
+    vec![w.to_string()]
+}
+
+#[test]
+fn no_problems() {
+    let src = "@page(title=`Test`, lang=`en`) {
+@h1 Title
+
+@image(alt=`A logo`) `logo.png` @image(alt=\"\") `divider.png`
+
+@h2 Section
+
+@href(`a.html`) {Link text} <a href=\"c.html\" aria_label=\"Close\"></a>
+
+<table><tr><th>Name</th></tr><tr><td>Papyri</td></tr></table>
+
+<label>Name <input type=\"text\"></label> <label for=\"email\">Email</label> <input id=\"email\"> <input type=\"submit\">
+}";
+    assert_eq!(Vec::<String>::new(), lint(src));
+}
+
+#[test]
+fn image_without_alt() {
+    assert_eq!(warning(Warning::MissingAltText), lint("@image `logo.png`"));
+}
+
+#[test]
+fn heading_level_skipped() {
+    assert_eq!(warning(Warning::HeadingLevelSkipped(1, 3)), lint("@h1 Title\n\n@h3 Subsection\n\n@h2 Section"));
+}
+
+#[test]
+fn empty_link_text() {
+    assert_eq!(warning(Warning::EmptyLinkText), lint("<a href=\"a.html\"> </a>"));
+}
+
+#[test]
+fn table_without_headers() {
+    assert_eq!(warning(Warning::TableWithoutHeaders), lint("<table><tr><td>1</td></tr></table>"));
+}
+
+#[test]
+fn input_without_label() {
+    assert_eq!(warning(Warning::MissingFormLabel("input".into())), lint("Name: <input id=\"name\">"));
+    assert_eq!(warning(Warning::MissingFormLabel("textarea".into())), lint("<label for=\"other\">Other</label> <textarea>Text</textarea>"));
+}
+
+#[test]
+fn html_without_lang() {
+    assert_eq!(warning(Warning::MissingLang), lint("@page(title=`Test`) {Hello}"));
+}
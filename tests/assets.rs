@@ -0,0 +1,111 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{self, ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+/// Compiles the source with an output directory and the given input files,
+/// and writes the output files. Returns the compiled HTML.
+fn compile(fs: &Rc<MemoryFileSystem>, src: &str) -> Result<String, errors::Diagnostics> {
+    compile_page(fs, None, src)
+}
+
+/// Compiles the source as the page with the given output path, relative to
+/// the output directory, and writes the output files.
+fn compile_page(fs: &Rc<MemoryFileSystem>, page_path: Option<&str>, src: &str) -> Result<String, errors::Diagnostics> {
+    let mut ctx = Context::new(ReportingLevel::Warning, Some(Path::new("/out")));
+    ctx.file_system = fs.clone();
+    ctx.page_path = page_path.map(PathBuf::from);
+    let out = ctx.compile_str(src)?;
+    
+    let out_files: Vec<_> = ctx.out_files.as_mut().unwrap().take_iter().collect();
+    for (path, file) in out_files {
+        ctx.write_out_file(&path, &file, true).unwrap();
+    }
+    Ok(out)
+}
+
+const LOGO: &[u8] = b"\x89PNG\r\n\x1a\n\x00\xff";
+
+#[test]
+fn copy_asset() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("img/logo.png", LOGO);
+    assert_eq!("<img src=\"img/logo.png\">", compile(&fs, "<img src=@asset::url `img/logo.png`>")?);
+    assert_eq!(Some(LOGO.to_vec()), fs.get_file("/out/img/logo.png"));
+    Ok(())
+}
+
+#[test]
+fn copy_asset_page_in_subdirectory() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("img/logo.png", LOGO);
+    assert_eq!("<img src=\"img/logo.png\">", compile_page(&fs, Some("docs/index.html"), "<img src=@asset::url `img/logo.png`>")?);
+    assert_eq!(Some(LOGO.to_vec()), fs.get_file("/out/docs/img/logo.png"));
+    assert_eq!(None, fs.get_file("/out/img/logo.png"));
+    Ok(())
+}
+
+#[test]
+fn copy_asset_parent_directory() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("logo.png", LOGO);
+    assert_eq!("<img src=\"../logo.png\">", compile_page(&fs, Some("docs/index.html"), "<img src=@asset::url `../logo.png`>")?);
+    assert_eq!(Some(LOGO.to_vec()), fs.get_file("/out/logo.png"));
+    Ok(())
+}
+
+#[test]
+fn copy_asset_hash() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("img/logo.png", LOGO);
+    fs.add_file("img/other.png", b"other".to_vec());
+    let url = compile(&fs, "@asset::url(hash=True) `img/logo.png`")?;
+    let other_url = compile(&fs, "@asset::url(hash=True) `img/other.png`")?;
+    
+    let name = url.strip_prefix("<p>img/logo.")
+        .and_then(|s| s.strip_suffix(".png</p>"))
+        .unwrap_or_else(|| panic!("{url}"));
+    assert!(name.chars().all(|c| c.is_ascii_hexdigit()), "{url}");
+    assert!(!other_url.contains(name), "{other_url}");
+    assert_eq!(Some(LOGO.to_vec()), fs.get_file(format!("/out/img/logo.{name}.png")));
+    assert_eq!(None, fs.get_file("/out/img/logo.png"));
+    
+    // The hash depends only on the contents
+    assert_eq!(url, compile(&fs, "@asset::url(hash=True) `img/logo.png`")?);
+    Ok(())
+}
+
+#[test]
+fn copy_asset_hash_no_extension() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("LICENSE", "MIT");
+    let url = compile(&fs, "@asset::url(hash=True) `LICENSE`")?;
+    assert!(url.starts_with("<p>LICENSE.") && url.len() == "<p>LICENSE.</p>".len() + 16, "{url}");
+    Ok(())
+}
+
+#[test]
+fn missing_asset() {
+    let fs = Rc::new(MemoryFileSystem::new());
+    common::assert_runtime_error(compile(&fs, "@asset::url `img/missing.png`"), |e| matches!(e, RuntimeError::FileReadError(..)));
+}
+
+#[test]
+fn asset_outside_out_dir() {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("logo.png", LOGO);
+    fs.add_file("pages/index.papyri", "");
+    common::assert_runtime_error(compile(&fs, "@asset::url `../logo.png`"), |e| matches!(e, RuntimeError::PathNotInOutDir(..)));
+}
+
+#[test]
+fn asset_no_out_dir() {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("logo.png", LOGO);
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    common::assert_runtime_error(ctx.compile_str("@asset::url `logo.png`"), |e| matches!(e, RuntimeError::WriteFileNotAllowed));
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{self, ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+const BIBTEX: &str = r#"
+@string{jcs = "Journal of Computer Science"}
+@comment{Not an entry}
+@article{smith2020,
+  author = {Smith, John and Jones, Karen Ann},
+  title = {{Typesetting} with Macros},
+  journal = jcs,
+  year = 2020,
+  volume = {12}, number = {3},
+  pages = {45--67},
+}
+@book(lee2019,
+  author = "Mei Lee and Tom Brown and {Acme Research Group}",
+  title = "Papers \& Books",
+  publisher = {Acme Press},
+  year = {2019},
+)
+"#;
+
+const CSL_JSON: &str = r#"[
+  {"id": "doe2018", "title": "Data Things", "author": [{"family": "Doe", "given": "Jane"}], "issued": {"date-parts": [[2018, 5]]}, "URL": "https://example.com/doe"}
+]"#;
+
+const ACCENTS_BIBTEX: &str = r#"
+@article{accents,
+  author = {Schr{\"o}dinger, Erwin and Erd\H{o}s, Paul and {\'E}mile Borel and Ana Mar\'{\i}a Garc\'ia and Stra\ss e, J{\o}rgen and \L{}ukasz {\c{C}}elik and Dvo\v{r}\'ak, Anton{\'\i}n and \AA{}ngstr\"om, Anders},
+  title = {\emph{Na\"{\i}ve} \`a la carte \& Co.},
+  year = 2001,
+}
+"#;
+
+/// Compiles the source, with the example bibliography files available.
+fn compile(src: &str) -> Result<String, errors::Diagnostics> {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("refs.bib", BIBTEX.as_bytes());
+    fs.add_file("refs.json", CSL_JSON.as_bytes());
+    fs.add_file("accents.bib", ACCENTS_BIBTEX.as_bytes());
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    ctx.compile_str(src)
+}
+
+#[test]
+fn cite_author_year() -> common::TestResult {
+    let out = compile("@bib::load `refs.bib`\n@bib::load `refs.json`\n\n@cite(`smith2020`). @cite(`lee2019`, `doe2018`) {p. 5}\n\n@bibliography.")?;
+    assert_eq!(
+        "<p><span class=\"citation\">(<a href=\"#bib_smith2020\">Smith and Jones 2020</a>)</span> <span class=\"citation\">(<a href=\"#bib_lee2019\">Lee et al. 2019</a>; <a href=\"#bib_doe2018\">Doe 2018</a>, p. 5)</span></p>\
+<section class=\"bibliography\">\
+<p id=\"bib_doe2018\">Doe, J. (2018). Data Things. <a href=\"https://example.com/doe\">https://example.com/doe</a></p>\
+<p id=\"bib_lee2019\">Lee, M., Brown, T. and Acme Research Group (2019). Papers &amp; Books. Acme Press.</p>\
+<p id=\"bib_smith2020\">Smith, J. and Jones, K. A. (2020). Typesetting with Macros. <i>Journal of Computer Science</i>, 12(3), 45\u{2013}67.</p>\
+</section>",
+        out,
+    );
+    Ok(())
+}
+
+#[test]
+fn cite_numeric() -> common::TestResult {
+    let out = compile("@bib::load `refs.bib`\n\n@cite(`smith2020`). @cite(`lee2019`, `smith2020`).\n\n@bibliography(style=`numeric`).")?;
+    assert_eq!(
+        "<p><span class=\"citation\">[<a href=\"#bib_smith2020\">1</a>]</span> <span class=\"citation\">[<a href=\"#bib_lee2019\">2</a>, <a href=\"#bib_smith2020\">1</a>]</span></p>\
+<section class=\"bibliography\">\
+<p id=\"bib_smith2020\">[1] Smith, J. and Jones, K. A. (2020). Typesetting with Macros. <i>Journal of Computer Science</i>, 12(3), 45\u{2013}67.</p>\
+<p id=\"bib_lee2019\">[2] Lee, M., Brown, T. and Acme Research Group (2019). Papers &amp; Books. Acme Press.</p>\
+</section>",
+        out,
+    );
+    Ok(())
+}
+
+#[test]
+fn cite_without_bibliography() -> common::TestResult {
+    let out = compile("@bib::load `refs.bib`\n\n@cite(`smith2020`).")?;
+    assert_eq!("<p><span class=\"citation\">(Smith and Jones 2020)</span></p>", out);
+    Ok(())
+}
+
+#[test]
+fn bibtex_accents() -> common::TestResult {
+    let out = compile("@bib::load `accents.bib`\n\n@cite(`accents`).\n\n@bibliography.")?;
+    assert_eq!(
+        "<p><span class=\"citation\">(<a href=\"#bib_accents\">Schr\u{f6}dinger et al. 2001</a>)</span></p>\
+<section class=\"bibliography\">\
+<p id=\"bib_accents\">Schr\u{f6}dinger, E., Erd\u{151}s, P., Borel, \u{c9}., Garc\u{ed}a, A. M., Stra\u{df}e, J., \u{c7}elik, \u{141}., Dvo\u{159}\u{e1}k, A. and \u{c5}ngstr\u{f6}m, A. (2001). Na\u{ef}ve \u{e0} la carte &amp; Co.</p>\
+</section>",
+        out,
+    );
+    Ok(())
+}
+
+#[test]
+fn cite_title() -> common::TestResult {
+    assert_eq!("<p><cite>Moby Dick</cite></p>", compile("@cite {Moby Dick}")?);
+    Ok(())
+}
+
+#[test]
+fn cite_content_is_key() {
+    let diagnostics = compile("@bib::load `refs.bib`\n\n@cite `smith2020`").unwrap_err();
+    assert_eq!(
+        vec!["'smith2020' is a citation key, but is cited as a title; use @cite(`smith2020`) to cite it"],
+        common::warnings(&diagnostics),
+    );
+}
+
+#[test]
+fn bibliography_only_cited() -> common::TestResult {
+    let out = compile("@bib::load `refs.bib`\n\n@bibliography.")?;
+    assert_eq!("", out);
+    Ok(())
+}
+
+#[test]
+fn cite_unknown_key() {
+    common::assert_runtime_error(
+        compile("@bib::load `refs.bib`\n\n@cite(`nobody`)."),
+        |e| matches!(e, RuntimeError::UnknownCitationKey(key) if key.as_ref() == "nobody"),
+    );
+}
+
+#[test]
+fn load_in_shared_module() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("refs.bib", BIBTEX.as_bytes());
+    fs.add_file("lib.papyri", "@bib::load `refs.bib`");
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    
+    // The module is only compiled for the first page, but its entries must
+    // be available to both
+    for page in ["@include `lib`\n\n@cite(`smith2020`).", "@include `lib`\n\n@cite(`lee2019`)."] {
+        ctx.reset();
+        let out = ctx.compile_str(page)?;
+        assert!(out.contains("<span class=\"citation\">"), "{out}");
+    }
+    Ok(())
+}
+
+#[test]
+fn cite_before_load() {
+    common::assert_runtime_error(
+        compile("@cite(`smith2020`).\n\n@bib::load `refs.bib`"),
+        |e| matches!(e, RuntimeError::UnknownCitationKey(..)),
+    );
+}
+
+#[test]
+fn bibliography_invalid_style() {
+    common::assert_runtime_error(
+        compile("@bibliography(style=`chicago`)."),
+        |e| matches!(e, RuntimeError::InvalidCitationStyle(..)),
+    );
+}
+
+#[test]
+fn bibtex_at_sign_in_comment() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("refs.bib", format!("% maintained by jane@example.com\nSee @ below.\n{BIBTEX}"));
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    let out = ctx.compile_str("@bib::load `refs.bib`\n\n@cite(`smith2020`).")?;
+    assert_eq!("<p><span class=\"citation\">(Smith and Jones 2020)</span></p>", out);
+    Ok(())
+}
+
+#[test]
+fn bibtex_parse_error() {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("bad.bib", b"@article{foo, title = {unclosed");
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    common::assert_runtime_error(
+        ctx.compile_str("@bib::load `bad.bib`"),
+        |e| matches!(e, RuntimeError::BibliographyParseError(..)),
+    );
+}
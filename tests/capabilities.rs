@@ -0,0 +1,76 @@
+mod common;
+
+use std::rc::Rc;
+use papyri_lang::compiler::{Capabilities, Context};
+use papyri_lang::errors::{self, ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+fn compile(capabilities: Capabilities, src: &str) -> Result<String, errors::Diagnostics> {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("site/lib.papyri", "@export @let(x=`site`).");
+    fs.add_file("site/data.txt", "data");
+    fs.add_file("secret.papyri", "@export @let(x=`secret`).");
+    fs.add_file("secret.txt", "secret");
+    
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    ctx.capabilities = capabilities;
+    ctx.compile_str(src)
+}
+
+#[test]
+fn default_allows_all() {
+    let result = compile(Capabilities::default(), "@include `secret` $x @file::read `secret.txt`");
+    assert_eq!("<p>secret secret</p>", result.unwrap());
+}
+
+#[test]
+fn import_in_root() {
+    let result = compile(Capabilities::sandboxed("site"), "@include `site/lib` $x");
+    assert_eq!("<p>site</p>", result.unwrap());
+}
+
+#[test]
+fn import_outside_root() {
+    let result = compile(Capabilities::sandboxed("site"), "@include `secret` $x");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::PathNotInRoot(..)));
+}
+
+#[test]
+fn import_escaping_root() {
+    let result = compile(Capabilities::sandboxed("site"), "@include `site/../secret` $x");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::PathNotInRoot(..)));
+}
+
+#[test]
+fn read_disabled() {
+    let result = compile(Capabilities::sandboxed("site"), "@file::read `site/data.txt`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::NotAllowed("@file::read")));
+}
+
+#[test]
+fn read_outside_root() {
+    let capabilities = Capabilities {file_read: true, ..Capabilities::sandboxed("site")};
+    assert_eq!("<p>data</p>", compile(capabilities.clone(), "@file::read `site/data.txt`").unwrap());
+    
+    let result = compile(capabilities, "@file::read `secret.txt`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::PathNotInRoot(..)));
+}
+
+#[test]
+fn list_disabled() {
+    let result = compile(Capabilities::sandboxed("site"), "@file::list `site`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::NotAllowed("@file::list")));
+}
+
+#[test]
+fn write_disabled() {
+    let result = compile(Capabilities::sandboxed("site"), "@file::write(`out.html`) foo");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::NotAllowed("@file::write")));
+}
+
+#[test]
+fn fetch_disabled() {
+    let result = compile(Capabilities::sandboxed("site"), "@fetch::raw `http://localhost/`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::NotAllowed("@fetch::raw")));
+}
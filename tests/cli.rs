@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// A temporary directory for running the `papyri` binary in, which is deleted
+/// when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir()
+            .join(format!("papyri-cli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+    
+    fn path(&self) -> &Path {
+        &self.0
+    }
+    
+    fn write(&self, rel_path: &str, contents: &str) {
+        let path = self.0.join(rel_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    
+    fn read(&self, rel_path: &str) -> Option<String> {
+        fs::read_to_string(self.0.join(rel_path)).ok()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The result of running the `papyri` binary.
+struct Output {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+/// Runs the `papyri` binary with the given arguments, in the given directory.
+fn papyri(dir: &TempDir, args: &[&str]) -> Output {
+    let out = Command::new(env!("CARGO_BIN_EXE_papyri"))
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    Output {
+        success: out.status.success(),
+        stdout: String::from_utf8(out.stdout).unwrap(),
+        stderr: String::from_utf8(out.stderr).unwrap(),
+    }
+}
+
+#[test]
+fn test_runner_passing() {
+    let dir = TempDir::new("test-passing");
+    dir.write("a.test.papyri", "@test::ok(`<p>foo</p>`, name=`ok`) `foo`\n\n@test::err(`RuntimeError::Raised`, name=`err`) `@raise bar`\n");
+    
+    let out = papyri(&dir, &["test"]);
+    assert!(out.success, "{}", out.stderr);
+    assert!(out.stdout.contains("    PASS ok\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("    PASS err\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("1 test file; 2 passed, 0 failed"), "{}", out.stdout);
+}
+
+#[test]
+fn test_runner_failing() {
+    let dir = TempDir::new("test-failing");
+    dir.write("a.test.papyri", "@test::ok(`<p>foo</p>`, name=`wrong_output`) `bar`\n\n@test::err(`RuntimeError`, name=`no_error`) `baz`\n\n@test::ok(`<p>x</p>`, name=`ok`) `x`\n");
+    
+    let out = papyri(&dir, &["test"]);
+    assert!(!out.success);
+    assert!(out.stdout.contains("    PASS ok\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("    FAIL wrong_output\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("--- expected\n        +++ actual\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("-<p>foo</p>\n        +<p>bar</p>\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("    FAIL no_error\n"), "{}", out.stdout);
+    assert!(out.stdout.contains("expected RuntimeError, but compiled successfully"), "{}", out.stdout);
+    assert!(out.stderr.contains("1 test file; 1 passed, 2 failed"), "{}", out.stderr);
+}
+
+#[test]
+fn test_runner_silent() {
+    let dir = TempDir::new("test-silent");
+    dir.write("a.test.papyri", "@test::ok(`<p>foo</p>`, name=`one`) `bar`\n\n@test::ok(`<p>foo</p>`, name=`two`) `baz`\n\n@test::ok(`<p>x</p>`, name=`ok`) `x`\n");
+    
+    let out = papyri(&dir, &["test", "--silent"]);
+    assert!(!out.success);
+    assert!(!out.stdout.contains("PASS"), "{}", out.stdout);
+    assert!(out.stdout.contains("FAIL one"), "{}", out.stdout);
+    assert!(out.stdout.contains("FAIL two"), "{}", out.stdout);
+    let path_lines = out.stdout.lines()
+        .filter(|line| line.ends_with("a.test.papyri"))
+        .count();
+    assert_eq!(1, path_lines, "{}", out.stdout);
+}
+
+#[test]
+fn snapshot_update() {
+    let dir = TempDir::new("snapshot-update");
+    dir.write("page.papyri", "Hello");
+    
+    let out = papyri(&dir, &["--snapshot", "snap", "--snapshot-update"]);
+    assert!(out.success, "{}", out.stderr);
+    assert!(out.stdout.contains("1 snapshot updated"), "{}", out.stdout);
+    assert_eq!(Some("<p>Hello</p>".to_string()), dir.read("snap/page.html"));
+    assert_eq!(None, dir.read("page.html"));
+}
+
+#[test]
+fn snapshot_match() {
+    let dir = TempDir::new("snapshot-match");
+    dir.write("page.papyri", "Hello");
+    dir.write("snap/page.html", "<p>Hello</p>");
+    
+    let out = papyri(&dir, &["--snapshot", "snap"]);
+    assert!(out.success, "{}", out.stderr);
+    assert!(out.stdout.contains("0 snapshots changed"), "{}", out.stdout);
+}
+
+#[test]
+fn snapshot_mismatch() {
+    let dir = TempDir::new("snapshot-mismatch");
+    dir.write("page.papyri", "Goodbye");
+    dir.write("snap/page.html", "<p>Hello</p>");
+    
+    let out = papyri(&dir, &["--snapshot", "snap"]);
+    assert!(!out.success);
+    assert!(out.stdout.contains("-<p>Hello</p>\n+<p>Goodbye</p>\n"), "{}", out.stdout);
+    assert!(out.stderr.contains("1 snapshot changed"), "{}", out.stderr);
+    assert_eq!(Some("<p>Hello</p>".to_string()), dir.read("snap/page.html"));
+}
+
+#[test]
+fn snapshot_stale() {
+    let dir = TempDir::new("snapshot-stale");
+    dir.write("page.papyri", "Hello");
+    dir.write("snap/page.html", "<p>Hello</p>");
+    dir.write("snap/old.html", "<p>Old</p>");
+    
+    let out = papyri(&dir, &["--snapshot", "snap"]);
+    assert!(!out.success);
+    assert!(out.stdout.contains("old.html (stale snapshot, no matching output)"), "{}", out.stdout);
+    assert!(dir.read("snap/old.html").is_some());
+    
+    let out = papyri(&dir, &["--snapshot", "snap", "--snapshot-update"]);
+    assert!(out.success, "{}", out.stderr);
+    assert_eq!(None, dir.read("snap/old.html"));
+    assert!(dir.read("snap/page.html").is_some());
+}
+
+#[test]
+fn asset_in_subdirectory() {
+    let dir = TempDir::new("asset-subdirectory");
+    dir.write("docs/index.papyri", "<img src=@asset::url `img/logo.png`>");
+    dir.write("docs/img/logo.png", "logo");
+    
+    let out = papyri(&dir, &["--out", "out"]);
+    assert!(out.success, "{}", out.stderr);
+    assert_eq!(Some("<img src=\"img/logo.png\">".to_string()), dir.read("out/docs/index.html"));
+    assert_eq!(Some("logo".to_string()), dir.read("out/docs/img/logo.png"));
+}
+
+#[test]
+fn ref_other_page() {
+    let dir = TempDir::new("ref-other-page");
+    dir.write("a.papyri", "@label(`fig`) <figure></figure>");
+    dir.write("b.papyri", "See @ref fig.");
+    
+    let out = papyri(&dir, &["--out", "out"]);
+    assert!(out.success, "{}", out.stderr);
+    assert_eq!(Some("<p>See <a href=\"a.html#fig\">Figure 1</a>.</p>".to_string()), dir.read("out/b.html"));
+    
+    // Only b.papyri has changed, but the label on a.papyri must still be found
+    dir.write("b.papyri", "Again, see @ref fig.");
+    let later = SystemTime::now() + Duration::from_secs(10);
+    fs::File::options()
+        .write(true)
+        .open(dir.path().join("b.papyri"))
+        .and_then(|f| f.set_modified(later))
+        .unwrap();
+    
+    let out = papyri(&dir, &["--out", "out", "--skip-unchanged"]);
+    assert!(out.success, "{}", out.stderr);
+    assert!(out.stderr.is_empty(), "{}", out.stderr);
+    assert!(out.stdout.contains("a.papyri (unchanged, skipping)"), "{}", out.stdout);
+    assert_eq!(Some("<p>Again, see <a href=\"a.html#fig\">Figure 1</a>.</p>".to_string()), dir.read("out/b.html"));
+}
+
+#[test]
+fn max_call_depth() {
+    let dir = TempDir::new("max-call-depth");
+    dir.write("page.papyri", "@fn down $n: int -> @match $n {0 -> done, _ -> @down @int::add($n, -1).}\n@down 20");
+    
+    let out = papyri(&dir, &["--max-call-depth", "10"]);
+    assert!(!out.success);
+    assert!(out.stderr.contains("maximum call depth of 10 exceeded"), "{}", out.stderr);
+    
+    let out = papyri(&dir, &["--max-call-depth", "30"]);
+    assert!(out.success, "{}", out.stderr);
+    assert_eq!(Some("<p>done</p>".to_string()), dir.read("page.html"));
+}
@@ -1,4 +1,53 @@
-pub type TestResult = Result<(), papyri_lang::errors::Diagnostics>;
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{Diagnostics, PapyriError, ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+#[allow(dead_code)]
+pub type TestResult = Result<(), Diagnostics>;
+
+/// Compiles the source with the given files in an in-memory file system.
+#[allow(dead_code)]
+pub fn compile_with_files(files: &[(&str, &str)], src: &str) -> Result<String, Diagnostics> {
+    let fs = Rc::new(MemoryFileSystem::new());
+    for &(path, contents) in files {
+        fs.add_file(path, contents);
+    }
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    ctx.compile_str(src)
+}
+
+/// Returns the diagnostics from a result which is expected to be an error.
+#[allow(dead_code)]
+pub fn expect_err<T>(result: Result<T, Diagnostics>) -> Diagnostics {
+    let Err(diagnostics) = result else {
+        panic!("No errors");
+    };
+    diagnostics
+}
+
+/// Asserts that the result is an error, and that some runtime error matches
+/// the predicate.
+#[allow(dead_code)]
+pub fn assert_runtime_error<T>(result: Result<T, Diagnostics>, predicate: impl Fn(&RuntimeError) -> bool) {
+    let diagnostics = expect_err(result);
+    assert!(
+        diagnostics.has_any(|d| matches!(d, PapyriError::RuntimeError(e) if predicate(e))),
+        "{diagnostics:?}",
+    );
+}
+
+/// Returns the messages of the given diagnostics, which must all be warnings.
+#[allow(dead_code)]
+pub fn warnings(diagnostics: &Diagnostics) -> Vec<String> {
+    diagnostics.iter()
+        .map(|d| match d.msg() {
+            PapyriError::Warning(w) => w.to_string(),
+            e => panic!("{e:?}"),
+        })
+        .collect()
+}
 
 #[macro_export]
 macro_rules! assert_ok {
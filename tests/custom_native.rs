@@ -0,0 +1,99 @@
+mod common;
+
+use papyri_lang::compiler::{Context, Value};
+use papyri_lang::errors::{self, PapyriError, ReportingLevel};
+use papyri_lang::parser::Type;
+
+fn context() -> Context {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    
+    ctx.native_func("greet")
+        .positional("name", Type::Str)
+        .named_default("greeting", Type::Str, "Hello".into())
+        .register(|args| {
+            let name: std::rc::Rc<str> = args.get_as("name")?;
+            let greeting: std::rc::Rc<str> = args.get_as("greeting")?;
+            Ok(format!("{greeting}, {name}!").into())
+        });
+    
+    ctx.native_func("app::sum")
+        .pos_spread("nums", Type::Int.list())
+        .register(|args| {
+            let nums: Vec<i64> = args.get_as("nums")?;
+            Ok(nums.into_iter().sum::<i64>().into())
+        });
+    
+    ctx.native_func("app::shout")
+        .content("text", Type::Str)
+        .register(|args| {
+            let text: std::rc::Rc<str> = args.get_as("text")?;
+            Ok(text.to_uppercase().into())
+        });
+    
+    ctx.native_func("app::user")
+        .implicit_default("user", Type::Str, "nobody".into())
+        .register(|args| {
+            let user = args.get("user")?;
+            Ok(args.new_dict([("name", user)]))
+        });
+    
+    ctx.native_func("app::fail")
+        .register(|_| Err(errors::RuntimeError::Raised("custom failure".into()).into()));
+    
+    ctx
+}
+
+fn compile(src: &str) -> Result<String, errors::Diagnostics> {
+    context().compile_str(src)
+}
+
+#[test]
+fn positional_and_default() -> common::TestResult {
+    assert_eq!("<p>Hello, world!</p>", compile("@greet(`world`).")?);
+    Ok(())
+}
+
+#[test]
+fn named_arg() -> common::TestResult {
+    assert_eq!("<p>Hi, world!</p>", compile("@greet(`world`, greeting=`Hi`).")?);
+    Ok(())
+}
+
+#[test]
+fn namespaced_spread() -> common::TestResult {
+    assert_eq!("<p>6</p>", compile("@app::sum(1, 2, 3).")?);
+    Ok(())
+}
+
+#[test]
+fn content_arg() -> common::TestResult {
+    assert_eq!("<p>HELLO</p>", compile("@app::shout hello")?);
+    Ok(())
+}
+
+#[test]
+fn implicit_arg() -> common::TestResult {
+    assert_eq!("<p>nobody alice</p>", compile("@let(u=@app::user.) $u::name @implicit(user=`alice`) @let(u=@app::user.) $u::name")?);
+    Ok(())
+}
+
+#[test]
+fn builtin_namespace_preserved() -> common::TestResult {
+    let mut ctx = context();
+    ctx.native_func("int::triple")
+        .positional("x", Type::Int)
+        .register(|args| Ok(Value::from(args.get_as::<i64>("x")? * 3)));
+    assert_eq!("<p>7 12</p>", ctx.compile_str("@int::add(3, 4). @int::triple(4).")?);
+    Ok(())
+}
+
+#[test]
+fn type_error() {
+    let diagnostics = common::expect_err(compile("@app::sum(1, `two`)."));
+    assert!(diagnostics.has_any(|d| matches!(d, PapyriError::TypeError(..))));
+}
+
+#[test]
+fn raised_error() {
+    common::assert_runtime_error(compile("@app::fail."), |e| matches!(e, errors::RuntimeError::Raised(..)));
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+#[test]
+fn read_json() -> common::TestResult {
+    let json = r#"{"title": "Glossary", "count": 2, "draft": false, "terms": [{"term": "AST"}, {"term": "HTML"}]}"#;
+    let src = "@let(g=@file::read_json `data/glossary.json`) {$g::title $g::count $g::draft @list::join(`, `) @list::map(@fn $t -> $t::term) $g::terms}";
+    assert_eq!("<p>Glossary 2 False AST, HTML</p>", common::compile_with_files(&[("data/glossary.json", json)], src)?);
+    Ok(())
+}
+
+#[test]
+fn read_json_key_mapping() -> common::TestResult {
+    let json = r#"{"release-date": "2023-01-01", "Release Notes": "x", "Hôpital": 1, "2023": 2, "$ref": 3}"#;
+    let src = "@list::join(`, `) @dict::keys @file::read_json `data.json`";
+    assert_eq!("<p>release_date, Release_Notes, Hopital, key_2023, ref</p>", common::compile_with_files(&[("data.json", json)], src)?);
+    Ok(())
+}
+
+#[test]
+fn read_json_key_collision() {
+    let json = r#"{"release-date": 1, "release_date": 2}"#;
+    let result = common::compile_with_files(&[("data.json", json)], "@file::read_json `data.json`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::DataKeyCollision(name) if name.as_ref() == "release_date"));
+}
+
+#[test]
+fn read_json_invalid() {
+    let result = common::compile_with_files(&[("data.json", "[1, 2")], "@file::read_json `data.json`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::JsonParseError(..)));
+}
+
+#[test]
+fn read_toml() -> common::TestResult {
+    let toml = "title = \"Changelog\"\n\n[[release]]\nversion = \"0.6.0\"\nbreaking-changes = 2\n\n[[release]]\nversion = \"0.5.0\"\nbreaking-changes = 0\n";
+    let src = "@let(c=@file::read_toml `changelog.toml`) {$c::title: @list::join(`, `) @list::map(@fn $r -> {$r::version ($r::breaking_changes)}) $c::release}";
+    assert_eq!("<p>Changelog: 0.6.0 (2), 0.5.0 (0)</p>", common::compile_with_files(&[("changelog.toml", toml)], src)?);
+    Ok(())
+}
+
+#[test]
+fn read_toml_invalid() {
+    let result = common::compile_with_files(&[("data.toml", "title = ")], "@file::read_toml `data.toml`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::TomlParseError(..)));
+}
+
+#[test]
+fn read_csv() -> common::TestResult {
+    let csv = "Name,Job Title\nAlice,Engineer\n\"Bob, Jr.\",Designer\n";
+    let src = "@list::join(`; `) @list::map(@fn $p -> {$p::Name: $p::Job_Title}) @file::read_csv `team.csv`";
+    assert_eq!("<p>Alice: Engineer; Bob, Jr.: Designer</p>", common::compile_with_files(&[("team.csv", csv)], src)?);
+    Ok(())
+}
+
+#[test]
+fn read_csv_ragged() {
+    let result = common::compile_with_files(&[("team.csv", "a,b\n1,2,3\n")], "@file::read_csv `team.csv`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::CsvParseError(..)));
+}
+
+#[test]
+fn read_data_disabled() {
+    let fs = Rc::new(MemoryFileSystem::new());
+    fs.add_file("data.json", "{}");
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.file_system = fs;
+    ctx.capabilities.file_read = false;
+    common::assert_runtime_error(ctx.compile_str("@file::read_json `data.json`"), |e| matches!(e, RuntimeError::NotAllowed("@file::read_json")));
+}
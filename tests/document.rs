@@ -0,0 +1,57 @@
+use papyri_lang::compiler::{Context, HTML, Value};
+use papyri_lang::errors::{ReportingLevel, Severity};
+
+fn context() -> Context {
+    Context::new(ReportingLevel::Warning, None)
+}
+
+#[test]
+fn html_tree() {
+    let mut ctx = context();
+    let doc = ctx.compile_document("<a href=\"foo.html\">bar</a>");
+    assert!(doc.diagnostics.is_empty());
+    
+    let HTML::Tag(p) = &doc.html else { panic!("Expected a tag, was {:?}", doc.html) };
+    assert_eq!("p", ctx.get_name(p.name_id()).as_ref());
+    
+    let HTML::Tag(a) = p.content() else { panic!("Expected a tag, was {:?}", p.content()) };
+    assert_eq!("a", ctx.get_name(a.name_id()).as_ref());
+    assert_eq!(Some(Some("foo.html")), a.get_attr(ctx.get_name_id("href").unwrap()));
+    assert!(matches!(a.content(), HTML::Text(t) if t.as_ref() == "bar"));
+}
+
+#[test]
+fn exports() {
+    let mut ctx = context();
+    let doc = ctx.compile_document("@export @let(title=`Hello`, count=3).");
+    assert!(doc.diagnostics.is_empty());
+    
+    let Value::Dict(exports) = &doc.exports else { panic!("Expected a dict, was {:?}", doc.exports) };
+    let title = exports.get(&ctx.get_name_id("title").unwrap());
+    let count = exports.get(&ctx.get_name_id("count").unwrap());
+    assert!(matches!(title, Some(Value::Str(s)) if s.as_ref() == "Hello"));
+    assert!(matches!(count, Some(Value::Int(3))));
+}
+
+#[test]
+fn diagnostics() {
+    let mut ctx = context();
+    let doc = ctx.compile_document("foo\n@raise bar");
+    
+    let diagnostics: Vec<_> = doc.diagnostics.iter().collect();
+    assert_eq!(1, diagnostics.len());
+    
+    let d = diagnostics[0];
+    assert_eq!(Severity::Error, d.severity());
+    assert_eq!("RuntimeError::Raised", d.msg().kind_name());
+    assert_eq!("<string>", d.range().path_str());
+    assert_eq!((2, 1), d.range().line_col());
+}
+
+#[test]
+fn output_despite_errors() {
+    let mut ctx = context();
+    let doc = ctx.compile_document("foo @raise bar");
+    assert_eq!(1, doc.diagnostics.num_errors);
+    assert!(!doc.html.is_empty());
+}
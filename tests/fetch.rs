@@ -0,0 +1,264 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use papyri_lang::compiler::{Context, FetchOptions};
+use papyri_lang::errors::{self, ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+/// A local HTTP server for testing, which records the requests it receives.
+/// Each request is answered by calling the handler with the request line,
+/// headers and body.
+struct TestServer {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    fn start(handler: fn(&str) -> String) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        
+        let requests_ref = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = v.trim().parse().unwrap();
+                    }
+                    request += &line;
+                    if line == "\r\n" || line.is_empty() { break; }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                request += &String::from_utf8(body).unwrap();
+                
+                let response = handler(&request);
+                requests_ref.lock().unwrap().push(request);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        
+        TestServer {url, requests}
+    }
+    
+    fn num_requests(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+    
+    fn last_request(&self) -> String {
+        self.requests.lock().unwrap().last().cloned().unwrap_or_default()
+    }
+}
+
+fn response(status: &str, headers: &str, body: &str) -> String {
+    format!("HTTP/1.1 {status}\r\nConnection: close\r\nContent-Length: {}\r\n{headers}\r\n{body}", body.len())
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("papyri-test-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn compile(options: &FetchOptions, src: &str) -> Result<String, errors::Diagnostics> {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.fetch_options = options.clone();
+    ctx.compile_str(src)
+}
+
+#[test]
+fn fetch_raw() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    assert_eq!("<p>hello</p>", compile(&FetchOptions::default(), &src).unwrap());
+    assert_eq!(1, server.num_requests());
+}
+
+#[test]
+fn cache_hit() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let options = FetchOptions {cache_dir: Some(cache_dir("hit")), ..FetchOptions::default()};
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!(1, server.num_requests());
+}
+
+#[test]
+fn cache_revalidate_etag() {
+    let server = TestServer::start(|request| {
+        if request.to_ascii_lowercase().contains("if-none-match: \"v1\"") {
+            response("304 Not Modified", "ETag: \"v1\"\r\n", "")
+        } else {
+            response("200 OK", "ETag: \"v1\"\r\n", "hello")
+        }
+    });
+    let options = FetchOptions {
+        cache_dir: Some(cache_dir("etag")),
+        cache_ttl: Duration::ZERO,
+        ..FetchOptions::default()
+    };
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!(2, server.num_requests());
+    assert!(server.last_request().to_ascii_lowercase().contains("if-none-match: \"v1\""));
+}
+
+#[test]
+fn cache_uses_file_system() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let fs = Rc::new(MemoryFileSystem::new());
+    let options = FetchOptions {cache_dir: Some(PathBuf::from("/cache")), ..FetchOptions::default()};
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    for _ in 0..2 {
+        let mut ctx = Context::new(ReportingLevel::Warning, None);
+        ctx.file_system = fs.clone();
+        ctx.fetch_options = options.clone();
+        assert_eq!("<p>hello</p>", ctx.compile_str(&src).unwrap());
+    }
+    assert_eq!(1, server.num_requests());
+    assert_eq!(2, fs.paths().len());
+    assert!(fs.paths().iter().all(|p| p.starts_with("/cache")));
+    assert!(!Path::new("/cache").exists());
+}
+
+#[test]
+fn errors_not_cached() {
+    let server = TestServer::start(|_| response("500 Internal Server Error", "", "oops"));
+    let options = FetchOptions {cache_dir: Some(cache_dir("errors")), ..FetchOptions::default()};
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    let _ = compile(&options, &src);
+    let _ = compile(&options, &src);
+    assert_eq!(2, server.num_requests());
+}
+
+#[test]
+fn offline_hit() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let mut options = FetchOptions {cache_dir: Some(cache_dir("offline_hit")), ..FetchOptions::default()};
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    options.offline = true;
+    options.cache_ttl = Duration::ZERO;
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!(1, server.num_requests());
+}
+
+#[test]
+fn offline_miss() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let options = FetchOptions {
+        cache_dir: Some(cache_dir("offline_miss")),
+        offline: true,
+        ..FetchOptions::default()
+    };
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    common::assert_runtime_error(compile(&options, &src), |e| matches!(e, RuntimeError::FetchOffline(..)));
+    assert_eq!(0, server.num_requests());
+}
+
+#[test]
+fn timeout() {
+    let server = TestServer::start(|_| {
+        std::thread::sleep(Duration::from_secs(2));
+        response("200 OK", "", "hello")
+    });
+    let options = FetchOptions {timeout: Some(Duration::from_millis(100)), ..FetchOptions::default()};
+    let src = format!("@fetch::raw `{}/a`", server.url);
+    
+    common::assert_runtime_error(compile(&options, &src), |e| matches!(e, RuntimeError::NetworkError(..)));
+}
+
+#[test]
+fn fetch_json() {
+    let server = TestServer::start(|_| response("200 OK", "Content-Type: application/json\r\n", r#"{"name": "papyri", "version": 6, "stable": true, "tags": ["a", "b"], "release-date": null, "score": 1.5}"#));
+    let src = format!("@let(r=@fetch::json `{}/a`) {{$r::name $r::version $r::stable @list::join(`, `) $r::tags @dict::get(`score`) $r @bool::from $r::release_date}}", server.url);
+    assert_eq!("<p>papyri 6 True a, b 1.5 False</p>", compile(&FetchOptions::default(), &src).unwrap());
+}
+
+#[test]
+fn fetch_json_invalid() {
+    let server = TestServer::start(|_| response("200 OK", "", "{"));
+    let src = format!("@fetch::json `{}/a`", server.url);
+    common::assert_runtime_error(compile(&FetchOptions::default(), &src), |e| matches!(e, RuntimeError::JsonParseError(..)));
+}
+
+#[test]
+fn request_options() {
+    let server = TestServer::start(|request| response("200 OK", "", request.lines().next().unwrap_or("")));
+    let src = format!("@fetch::raw(method=`post`, headers=@dict::new(x_api_key=`secret`)., body=`hello`) `{}/a`", server.url);
+    assert_eq!("<p>POST /a HTTP/1.1</p>", compile(&FetchOptions::default(), &src).unwrap());
+    
+    let request = server.last_request().to_ascii_lowercase();
+    assert!(request.contains("x-api-key: secret"), "{request}");
+    assert!(request.ends_with("\r\n\r\nhello"), "{request}");
+}
+
+#[test]
+fn invalid_method() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let src = format!("@fetch::raw(method=`not a method`) `{}/a`", server.url);
+    common::assert_runtime_error(compile(&FetchOptions::default(), &src), |e| matches!(e, RuntimeError::FetchInvalidMethod(..)));
+    assert_eq!(0, server.num_requests());
+}
+
+#[test]
+fn expected_status() {
+    let server = TestServer::start(|_| response("404 Not Found", "", "not found"));
+    let src = format!("@fetch::raw(status=404) `{}/a`", server.url);
+    assert_eq!("<p>not found</p>", compile(&FetchOptions::default(), &src).unwrap());
+    
+    let src = format!("@fetch::raw(status=200) `{}/a`", server.url);
+    common::assert_runtime_error(compile(&FetchOptions::default(), &src), |e| matches!(e, RuntimeError::FetchUnexpectedStatus(_, 200, 404)));
+}
+
+#[test]
+fn cache_only_get() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let options = FetchOptions {cache_dir: Some(cache_dir("only_get")), ..FetchOptions::default()};
+    let src = format!("@fetch::raw(method=`POST`) `{}/a`", server.url);
+    
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!("<p>hello</p>", compile(&options, &src).unwrap());
+    assert_eq!(2, server.num_requests());
+}
+
+#[test]
+fn cache_by_headers() {
+    let server = TestServer::start(|_| response("200 OK", "", "hello"));
+    let options = FetchOptions {cache_dir: Some(cache_dir("by_headers")), ..FetchOptions::default()};
+    let src_a = format!("@fetch::raw(headers=@dict::new(accept=`text/plain`).) `{}/a`", server.url);
+    let src_b = format!("@fetch::raw(headers=@dict::new(accept=`text/html`).) `{}/a`", server.url);
+    
+    compile(&options, &src_a).unwrap();
+    compile(&options, &src_b).unwrap();
+    compile(&options, &src_a).unwrap();
+    assert_eq!(2, server.num_requests());
+}
+
+#[test]
+fn fetch_html_sanitize() {
+    let server = TestServer::start(|_| response("200 OK", "Content-Type: text/html\r\n", r#"<!DOCTYPE html><html><head><title>Test</title></head><body><p onclick="alert(1)">Hello<script>alert(2)</script> <a href="javascript:alert(3)">world</a></p></body></html>"#));
+    let src = format!("@fetch::html(sanitize=True) `{}/a`", server.url);
+    assert_eq!("<p>Hello <a>world</a></p>", compile(&FetchOptions::default(), &src).unwrap());
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::path::Path;
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{self, ReportingLevel};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+fn context(fs: &Rc<MemoryFileSystem>, out_dir: Option<&Path>) -> Context {
+    let mut ctx = Context::new(ReportingLevel::Warning, out_dir);
+    ctx.file_system = fs.clone();
+    ctx
+}
+
+#[test]
+fn import() -> common::TestResult {
+    let lib = "@export @fn double($_x: int) . -> @int::add($_x, $_x).";
+    assert_eq!("<p>6</p>", common::compile_with_files(&[("lib.papyri", lib)], "@let(lib=@import lib) @lib::double(3).")?);
+    Ok(())
+}
+
+#[test]
+fn include() -> common::TestResult {
+    let lib = "@export @let(greeting=`Hello`).";
+    assert_eq!("<p>Hello, world!</p>", common::compile_with_files(&[("dir/lib.papyri", lib)], "@include `dir/lib` $greeting, world!")?);
+    Ok(())
+}
+
+#[test]
+fn import_missing() {
+    let diagnostics = common::expect_err(common::compile_with_files(&[], "@import lib"));
+    assert!(diagnostics.has_any(|d| matches!(d, errors::PapyriError::ModuleError(errors::ModuleError::IOError(..)))));
+}
+
+#[test]
+fn read_file() -> common::TestResult {
+    assert_eq!("<p>some text</p>", common::compile_with_files(&[("data/a.txt", "some text")], "@file::read `data/a.txt`")?);
+    Ok(())
+}
+
+#[test]
+fn list_files() -> common::TestResult {
+    let files = [
+        ("pages/b.papyri", ""),
+        ("pages/a.papyri", ""),
+        ("pages/sub/c.papyri", ""),
+        ("pages/d.txt", ""),
+    ];
+    assert_eq!("<p>pages/a, pages/b, pages/sub/c</p>", common::compile_with_files(&files, "@let(x=@file::list `pages`) @list::join(`, `) $x")?);
+    Ok(())
+}
+
+#[test]
+fn write_out_files() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    let mut ctx = context(&fs, Some(Path::new("/out")));
+    ctx.compile_str("@file::write(`page.html`) {Hello}")?;
+    
+    let out_files: Vec<_> = ctx.out_files.as_mut().unwrap().take_iter().collect();
+    for (path, file) in out_files {
+        ctx.write_out_file(&path, &file, true).unwrap();
+    }
+    assert_eq!(Some(b"Hello".to_vec()), fs.get_file("/out/page.html"));
+    Ok(())
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::ReportingLevel;
+
+assert_ok! {
+    footnotes_at_end(
+        "Foo@footnotes::note {First.} bar@footnotes::note {Second.}",
+        "<p>Foo<sup id=\"footnote_ref_1\" class=\"footnote-ref\"><a href=\"#footnote_1\">[1]</a></sup> bar<sup id=\"footnote_ref_2\" class=\"footnote-ref\"><a href=\"#footnote_2\">[2]</a></sup></p><section class=\"footnotes\"><hr><p id=\"footnote_1\"><a href=\"#footnote_ref_1\" class=\"footnote-backref\">[1]</a> First.</p><p id=\"footnote_2\"><a href=\"#footnote_ref_2\" class=\"footnote-backref\">[2]</a> Second.</p></section>",
+    );
+    
+    footnotes_render(
+        "Foo@footnotes::note {First.}\n\n@footnotes::render.\n\nBar",
+        "<p>Foo<sup id=\"footnote_ref_1\" class=\"footnote-ref\"><a href=\"#footnote_1\">[1]</a></sup></p><section class=\"footnotes\"><hr><p id=\"footnote_1\"><a href=\"#footnote_ref_1\" class=\"footnote-backref\">[1]</a> First.</p></section><p>Bar</p>",
+    );
+    
+    footnotes_render_multiple(
+        "A@footnotes::note {First.}\n\n@footnotes::render.\n\nB@footnotes::note {Second.}\n\n@footnotes::render.",
+        "<p>A<sup id=\"footnote_ref_1\" class=\"footnote-ref\"><a href=\"#footnote_1\">[1]</a></sup></p><section class=\"footnotes\"><hr><p id=\"footnote_1\"><a href=\"#footnote_ref_1\" class=\"footnote-backref\">[1]</a> First.</p></section><p>B<sup id=\"footnote_ref_2\" class=\"footnote-ref\"><a href=\"#footnote_2\">[2]</a></sup></p><section class=\"footnotes\"><hr><p id=\"footnote_2\"><a href=\"#footnote_ref_2\" class=\"footnote-backref\">[2]</a> Second.</p></section>",
+    );
+    
+    footnotes_render_empty(
+        "Foo\n\n@footnotes::render.",
+        "<p>Foo</p>",
+    );
+    
+    footnotes_end_of_body(
+        "<html><body><p>Foo@footnotes::note {First.}</p></body></html>",
+        "<html><body><p>Foo<sup id=\"footnote_ref_1\" class=\"footnote-ref\"><a href=\"#footnote_1\">[1]</a></sup></p><section class=\"footnotes\"><hr><p id=\"footnote_1\"><a href=\"#footnote_ref_1\" class=\"footnote-backref\">[1]</a> First.</p></section></body></html>",
+    );
+    
+    footnotes_id_reserved(
+        "<p id=\"footnote_1\">Foo@footnotes::note {First.}</p>",
+        "<p id=\"footnote_1\">Foo<sup id=\"footnote_ref_1\" class=\"footnote-ref\"><a href=\"#footnote_1_2\">[1]</a></sup></p><section class=\"footnotes\"><hr><p id=\"footnote_1_2\"><a href=\"#footnote_ref_1\" class=\"footnote-backref\">[1]</a> First.</p></section>",
+    );
+}
+
+assert_err! {
+    footnote_block_body("@footnotes::note {\n\nFoo\n\nBar\n\n}", TypeError::ExpectedWas);
+}
+
+#[test]
+fn footnotes_text() -> common::TestResult {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    let doc = ctx.compile_document("Foo@footnotes::note {First.}");
+    assert!(doc.diagnostics.is_empty(), "{:?}", doc.diagnostics);
+    let mut out = Vec::new();
+    ctx.render(&doc.html, false, &mut out).unwrap();
+    assert_eq!("Foo[1]\n\n\n\u{2015}\n\n[1] First.\n\n\n", String::from_utf8(out).unwrap());
+    Ok(())
+}
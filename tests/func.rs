@@ -118,3 +118,15 @@ assert_matches! {
         "=[1, 2, 3]",
     );
 }
+
+assert_err! {
+    unbounded_recursion(
+        "@fn foo . -> @foo.\n@foo.",
+        RuntimeError::CallDepthExceeded,
+    );
+    
+    unbounded_mutual_recursion(
+        "@fn foo . -> @bar.\n@fn bar . -> @list::map(@fn $_ -> @foo.) [1]\n@foo.",
+        RuntimeError::CallDepthExceeded,
+    );
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use std::path::Path;
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::ReportingLevel;
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+assert_ok! {
+    encode_scalars(
+        "@json::encode [True, 23, `foo \"bar\"`]",
+        r#"<p>[true,23,"foo \"bar\""]</p>"#,
+    );
+    
+    encode_dict(
+        "@json::encode @dict::new(name=`papyri`, tags=[`a`, `b`], empty=[]).",
+        r#"<p>{"name":"papyri","tags":["a","b"],"empty":[]}</p>"#,
+    );
+    
+    encode_html(
+        "@json::encode [<b>bold</b>, @list::get(0) [.]]",
+        r#"<p>["&lt;b&gt;bold&lt;/b&gt;",null]</p>"#,
+    );
+    
+    encode_pretty(
+        "@json::encode(pretty=True) [1, 2]",
+        "<p>[\n  1,\n  2\n]</p>",
+    );
+}
+
+assert_err! {
+    encode_function("@json::encode [$list::map]", TypeError::NotJsonSerializable);
+    encode_regex("@json::encode @regex::compile `a+`", TypeError::NotJsonSerializable);
+}
+
+#[test]
+fn write_json() -> common::TestResult {
+    let fs = Rc::new(MemoryFileSystem::new());
+    let mut ctx = Context::new(ReportingLevel::Warning, Some(Path::new("/out")));
+    ctx.file_system = fs.clone();
+    ctx.compile_str("@file::write_json(`index.json`) [@dict::new(title=`Home`, url=`/`)., @dict::new(title=`<About>`, url=`/about`).]")?;
+    
+    let out_files: Vec<_> = ctx.out_files.as_mut().unwrap().take_iter().collect();
+    for (path, file) in out_files {
+        ctx.write_out_file(&path, &file, true).unwrap();
+    }
+    let expected = r#"[{"title":"Home","url":"/"},{"title":"<About>","url":"/about"}]"#;
+    assert_eq!(Some(expected.as_bytes().to_vec()), fs.get_file("/out/index.json"));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::path::Path;
+use papyri_lang::compiler::{Context, CrossReferences};
+use papyri_lang::errors::{PapyriError, ReportingLevel, Warning};
+
+assert_ok! {
+    ref_figure(
+        "@ref fig_a\n\n@label(`fig_a`) <figure><figcaption>Caption.</figcaption></figure>",
+        "<p><a href=\"#fig_a\">Figure 1</a></p><figure id=\"fig_a\"><figcaption>Figure 1: Caption.</figcaption></figure>",
+    );
+    
+    ref_table_numbered(
+        "@label(`t1`) <table><caption>One.</caption></table>\n\n@label(`t2`) <table><caption>Two.</caption></table>\n\n@ref t2",
+        "<table id=\"t1\"><caption>Table 1: One.</caption></table><table id=\"t2\"><caption>Table 2: Two.</caption></table><p><a href=\"#t2\">Table 2</a></p>",
+    );
+    
+    ref_section(
+        "@h1 Title\n\n@h2 Intro\n\n@h2 Design\n\n@label(`sec_impl`) @h3 Implementation\n\n@ref sec_impl",
+        "<h1>Title</h1><h2>Intro</h2><h2>Design</h2><h3 id=\"sec_impl\">Implementation</h3><p><a href=\"#sec_impl\">Section 2.1</a></p>",
+    );
+    
+    ref_section_element(
+        "@h2 Intro\n\n@label(`s`) <section><h2>Design</h2></section>\n\n@ref s",
+        "<h2>Intro</h2><section id=\"s\"><h2>Design</h2></section><p><a href=\"#s\">Section 2</a></p>",
+    );
+    
+    ref_title(
+        "@label(`top`) @h1 Title\n\n@h2 Intro\n\n@h2 Design\n\n@ref top",
+        "<h1 id=\"top\">Title</h1><h2>Intro</h2><h2>Design</h2><p><a href=\"#top\">Title</a></p>",
+    );
+    
+    ref_explicit_kind(
+        "@label(`thm`, kind=`Theorem`) <div>All cats are grey.</div>\n\n@ref thm",
+        "<div id=\"thm\">All cats are grey.</div><p><a href=\"#thm\">Theorem 1</a></p>",
+    );
+    
+    ref_existing_id(
+        "@ref fig\n\n@label(`fig`) <figure id=\"arch\"></figure>",
+        "<p><a href=\"#arch\">Figure 1</a></p><figure id=\"arch\"></figure>",
+    );
+}
+
+assert_err! {
+    ref_unknown_label("@ref missing", Warning::UnknownLabel);
+    label_duplicate("@label(`f`) <figure></figure>\n\n@label(`f`) <figure></figure>", Warning::DuplicateLabel);
+    label_not_element("@label(`f`) {foo}", RuntimeError::LabelNotElement);
+    label_kind_unknown("@label(`f`) <div>foo</div>", RuntimeError::LabelKindUnknown);
+}
+
+#[test]
+fn ref_other_page() {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    let mut cross_refs = CrossReferences::new();
+    
+    let target = ctx.compile_document("@label(`fig`) <figure></figure>");
+    assert!(target.diagnostics.is_empty(), "{:?}", target.diagnostics);
+    cross_refs.add_page(Path::new("out/chapters/two.html"), &target.html);
+    
+    let page = ctx.compile_document("See @ref fig and @ref missing.");
+    assert!(page.diagnostics.is_empty(), "{:?}", page.diagnostics);
+    assert!(CrossReferences::has_unresolved(&page.html));
+    let html = cross_refs.resolve(Path::new("out/one.html"), &page.html, &mut ctx);
+    
+    let diagnostics = ctx.diagnostics.take();
+    let warnings: Vec<_> = diagnostics.iter()
+        .map(|d| match d.msg() {
+            PapyriError::Warning(Warning::UnknownLabel(name)) => name.to_string(),
+            e => panic!("{e:?}"),
+        })
+        .collect();
+    assert_eq!(vec!["missing"], warnings);
+    
+    let mut out = Vec::new();
+    ctx.render(&html, true, &mut out).unwrap();
+    assert_eq!("<p>See <a href=\"chapters/two.html#fig\">Figure 1</a> and ??.</p>", String::from_utf8(out).unwrap());
+}
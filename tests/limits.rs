@@ -0,0 +1,72 @@
+mod common;
+
+use std::time::{Duration, Instant};
+use papyri_lang::compiler::{Context, Limits};
+use papyri_lang::errors::{self, PapyriError, ReportingLevel, RuntimeError};
+
+/// Makes 2^n recursive calls, where n is the length of the list.
+const EXPONENTIAL: &str = "@fn f($_l: any list) . -> @match $_l {[] -> x, [_, *$t] -> [@f($t)., @f($t).]}\n";
+
+fn compile(limits: Limits, src: &str) -> Result<String, errors::Diagnostics> {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.limits = limits;
+    ctx.compile_str(src)
+}
+
+/// Recurses to the given depth, with a `@match` in each call.
+const COUNTDOWN: &str = "@fn down $n: int -> @match $n {0 -> done, _ -> @down @int::add($n, -1).}\n";
+
+#[test]
+fn deep_recursion() {
+    let src = format!("{COUNTDOWN}@down 1000");
+    assert_eq!("<p>done</p>", compile(Limits::default(), &src).unwrap());
+}
+
+#[test]
+fn call_depth_limit() {
+    let limits = Limits {max_call_depth: 50, ..Limits::default()};
+    let src = format!("{COUNTDOWN}@down 49");
+    assert_eq!("<p>done</p>", compile(limits.clone(), &src).unwrap());
+    
+    let src = format!("{COUNTDOWN}@down 50");
+    let diagnostics = common::expect_err(compile(limits, &src));
+    assert!(diagnostics.has_any(|d| matches!(d, PapyriError::RuntimeError(RuntimeError::CallDepthExceeded(50)))));
+}
+
+#[test]
+fn within_step_limit() {
+    let limits = Limits {max_steps: Some(1000), ..Limits::default()};
+    let src = format!("{EXPONENTIAL}@list::len @f([1, 1]).");
+    assert_eq!("<p>2</p>", compile(limits, &src).unwrap());
+}
+
+#[test]
+fn step_limit_exceeded() {
+    let limits = Limits {max_steps: Some(1000), ..Limits::default()};
+    let src = format!("{EXPONENTIAL}@f([1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]).");
+    let diagnostics = common::expect_err(compile(limits, &src));
+    assert_eq!(1, diagnostics.num_errors);
+    assert!(diagnostics.has_any(|d| matches!(d, PapyriError::RuntimeError(RuntimeError::StepLimitExceeded(1000)))));
+}
+
+#[test]
+fn time_limit_exceeded() {
+    let limits = Limits {timeout: Some(Duration::from_millis(50)), ..Limits::default()};
+    let src = format!("{EXPONENTIAL}@f([1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]).");
+    
+    let start = Instant::now();
+    let diagnostics = common::expect_err(compile(limits, &src));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(1, diagnostics.num_errors);
+    assert!(diagnostics.has_any(|d| matches!(d, PapyriError::RuntimeError(RuntimeError::TimeLimitExceeded(..)))));
+}
+
+#[test]
+fn budget_resets_between_jobs() {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    ctx.limits.max_steps = Some(1000);
+    let src = format!("{EXPONENTIAL}@list::len @f([1, 1, 1, 1]).");
+    for _ in 0..10 {
+        assert_eq!("<p>2</p>", ctx.compile_str(&src).unwrap());
+    }
+}
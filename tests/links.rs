@@ -0,0 +1,83 @@
+use std::path::Path;
+use std::rc::Rc;
+use papyri_lang::compiler::{Context, LinkChecker};
+use papyri_lang::errors::{self, PapyriError, ReportingLevel, Warning};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+/// Compiles each page, then checks the links between them. The other files
+/// exist in the output directory, but are not pages.
+fn check_links(pages: &[(&str, &str)], files: &[&str]) -> errors::Diagnostics {
+    let fs = Rc::new(MemoryFileSystem::new());
+    for &path in files {
+        fs.add_file(path, "");
+    }
+    let mut ctx = Context::new(ReportingLevel::Warning, Some(Path::new("/out")));
+    ctx.file_system = fs;
+    
+    let mut checker = LinkChecker::new(Some(Path::new("/out")));
+    for &(path, src) in pages {
+        let doc = ctx.compile_document(src);
+        assert!(doc.diagnostics.is_empty(), "{:?}", doc.diagnostics);
+        checker.add_page(Path::new(path), &doc.html);
+    }
+    checker.check(&mut ctx);
+    ctx.diagnostics.take()
+}
+
+fn count_broken(diagnostics: &errors::Diagnostics) -> (usize, usize) {
+    let mut links = 0;
+    let mut fragments = 0;
+    for d in diagnostics.iter() {
+        match d.msg() {
+            PapyriError::Warning(Warning::BrokenLink(..)) => links += 1,
+            PapyriError::Warning(Warning::BrokenLinkFragment(..)) => fragments += 1,
+            _ => panic!("{diagnostics:?}"),
+        }
+    }
+    (links, fragments)
+}
+
+#[test]
+fn valid_links() {
+    let diagnostics = check_links(&[
+        ("/out/index.html", "@href(`docs/a.html`) A, @href(`docs/a.html#intro`) {A intro}, <img src=\"img/logo.png\">, @href(`docs/`) Docs"),
+        ("/out/docs/a.html", "<h1 id=\"intro\">Intro</h1> @href(`../index.html`) Home, @href(`#intro`) Top, @href(`/img/logo.png`) Logo"),
+        ("/out/docs/index.html", "@href(`a.html?x=1#intro`) A"),
+    ], &["/out/img/logo.png"]);
+    assert_eq!((0, 0), count_broken(&diagnostics));
+}
+
+#[test]
+fn external_links_not_checked() {
+    let diagnostics = check_links(&[
+        ("/out/index.html", "@href(`https://example.com/missing`) A, @href(`mailto:someone@example.com`) B, @href(`//example.com/c`) C"),
+    ], &[]);
+    assert_eq!((0, 0), count_broken(&diagnostics));
+}
+
+#[test]
+fn broken_links() {
+    let diagnostics = check_links(&[
+        ("/out/index.html", "@href(`missing.html`) A, <img src=\"img/missing.png\">, <link rel=\"stylesheet\" href=\"/style.css\">"),
+    ], &[]);
+    assert_eq!((3, 0), count_broken(&diagnostics));
+}
+
+#[test]
+fn broken_fragments() {
+    let diagnostics = check_links(&[
+        ("/out/index.html", "<h2 id=\"here\">Here</h2> @href(`#here`) A, @href(`#nowhere`) B, @href(`a.html#intro`) C"),
+        ("/out/a.html", "Intro"),
+    ], &[]);
+    assert_eq!((0, 2), count_broken(&diagnostics));
+}
+
+#[test]
+fn broken_link_range() {
+    let diagnostics = check_links(&[
+        ("/out/index.html", "First line\n\n@href(`missing.html`) A"),
+    ], &[]);
+    let d = diagnostics.iter().next().expect("No diagnostics");
+    assert_eq!("<string>", d.range().path_str());
+    assert_eq!(3, d.range().line_col().0);
+}
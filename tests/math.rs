@@ -0,0 +1,130 @@
+mod common;
+
+assert_ok! {
+    scripts(
+        "@math `x^2 + y_1`",
+        "<p><math alttext=\"x^2 + y_1\"><mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><msub><mi>y</mi><mn>1</mn></msub></mrow></math></p>",
+    );
+    
+    subsup(
+        "@math `x_i^{n-1}`",
+        "<p><math alttext=\"x_i^{n-1}\"><msubsup><mi>x</mi><mi>i</mi><mrow><mi>n</mi><mo>\u{2212}</mo><mn>1</mn></mrow></msubsup></math></p>",
+    );
+    
+    frac(
+        "@math `\\frac{1}{2}`",
+        "<p><math alttext=\"\\frac{1}{2}\"><mfrac><mn>1</mn><mn>2</mn></mfrac></math></p>",
+    );
+    
+    frac_single_chars(
+        "@math `\\frac12`",
+        "<p><math alttext=\"\\frac12\"><mfrac><mn>1</mn><mn>2</mn></mfrac></math></p>",
+    );
+    
+    sqrt(
+        "@math `\\sqrt{x} + \\sqrt[3]{y}`",
+        "<p><math alttext=\"\\sqrt{x} + \\sqrt[3]{y}\"><mrow><msqrt><mi>x</mi></msqrt><mo>+</mo><mroot><mi>y</mi><mn>3</mn></mroot></mrow></math></p>",
+    );
+    
+    sqrt_nested_index(
+        "@math `\\sqrt[\\sqrt[3]{x}]{y}`",
+        "<p><math alttext=\"\\sqrt[\\sqrt[3]{x}]{y}\"><mroot><mi>y</mi><mroot><mi>x</mi><mn>3</mn></mroot></mroot></math></p>",
+    );
+    
+    decimal(
+        "@math `2.5x`",
+        "<p><math alttext=\"2.5x\"><mrow><mn>2.5</mn><mi>x</mi></mrow></math></p>",
+    );
+    
+    greek(
+        "@math `\\alpha \\Omega`",
+        "<p><math alttext=\"\\alpha \\Omega\"><mrow><mi>\u{3b1}</mi><mi mathvariant=\"normal\">\u{3a9}</mi></mrow></math></p>",
+    );
+    
+    operators(
+        "@math `a \\le b \\times c`",
+        "<p><math alttext=\"a \\le b \\times c\"><mrow><mi>a</mi><mo>\u{2264}</mo><mi>b</mi><mo>\u{d7}</mo><mi>c</mi></mrow></math></p>",
+    );
+    
+    function_name(
+        "@math `\\sin x`",
+        "<p><math alttext=\"\\sin x\"><mrow><mi>sin</mi><mi>x</mi></mrow></math></p>",
+    );
+    
+    text(
+        "@math `x \\text{ if } y`",
+        "<p><math alttext=\"x \\text{ if } y\"><mrow><mi>x</mi><mtext> if </mtext><mi>y</mi></mrow></math></p>",
+    );
+    
+    mathbb(
+        "@math `\\mathbb{R}`",
+        "<p><math alttext=\"\\mathbb{R}\"><mi mathvariant=\"double-struck\">R</mi></math></p>",
+    );
+    
+    left_right(
+        "@math `\\left( x \\right]`",
+        "<p><math alttext=\"\\left( x \\right]\"><mrow><mo>(</mo><mi>x</mi><mo>]</mo></mrow></math></p>",
+    );
+    
+    sum_inline(
+        "@math `\\sum_i x`",
+        "<p><math alttext=\"\\sum_i x\"><mrow><msub><mo>\u{2211}</mo><mi>i</mi></msub><mi>x</mi></mrow></math></p>",
+    );
+    
+    sum_display(
+        "@math(display=True) `\\sum_{i=1}^n i`",
+        "<p><math display=\"block\" alttext=\"\\sum_{i=1}^n i\"><mrow><munderover><mo>\u{2211}</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover><mi>i</mi></mrow></math></p>",
+    );
+    
+    pmatrix(
+        "@math `\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}`",
+        "<p><math alttext=\"\\begin{pmatrix} a &amp; b \\\\ c &amp; d \\end{pmatrix}\"><mrow><mo>(</mo><mtable><mtr><mtd><mi>a</mi></mtd><mtd><mi>b</mi></mtd></mtr><mtr><mtd><mi>c</mi></mtd><mtd><mi>d</mi></mtd></mtr></mtable><mo>)</mo></mrow></math></p>",
+    );
+    
+    cases_trailing_row(
+        "@math `\\begin{cases} 1 & x \\\\ 0 & y \\\\ \\end{cases}`",
+        "<p><math alttext=\"\\begin{cases} 1 &amp; x \\\\ 0 &amp; y \\\\ \\end{cases}\"><mrow><mo>{</mo><mtable columnalign=\"left left\"><mtr><mtd><mn>1</mn></mtd><mtd><mi>x</mi></mtd></mtr><mtr><mtd><mn>0</mn></mtd><mtd><mi>y</mi></mtd></mtr></mtable></mrow></math></p>",
+    );
+    
+    label_equation(
+        "@label(`euler`) @math(display=True) `e^{i\\pi}`\n\nSee @ref euler.",
+        "<math display=\"block\" alttext=\"e^{i\\pi}\" id=\"euler\"><msup><mi>e</mi><mrow><mi>i</mi><mi>\u{3c0}</mi></mrow></msup></math><p>See <a href=\"#euler\">Equation 1</a>.</p>",
+    );
+}
+
+assert_err! {
+    unknown_command(
+        "@math `\\foo`",
+        RuntimeError::MathSyntaxError,
+    );
+    
+    unknown_environment(
+        "@math `\\begin{foo} x \\end{foo}`",
+        RuntimeError::MathSyntaxError,
+    );
+    
+    unmatched_brace(
+        "@math `{x`",
+        RuntimeError::MathSyntaxError,
+    );
+    
+    unmatched_close_brace(
+        "@math `x}`",
+        RuntimeError::MathSyntaxError,
+    );
+    
+    left_without_right(
+        "@math `\\left( x`",
+        RuntimeError::MathSyntaxError,
+    );
+    
+    ampersand_outside_environment(
+        "@math `a & b`",
+        RuntimeError::MathSyntaxError,
+    );
+    
+    double_superscript(
+        "@math `x^2^3`",
+        RuntimeError::MathSyntaxError,
+    );
+}
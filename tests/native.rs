@@ -29,4 +29,5 @@ assert_matches! {
 
 assert_err! {
     raise("@raise `foobar`", RuntimeError::Raised);
+    test_outside_test_file("@test::ok(`<p>foo</p>`) `foo`", RuntimeError::TestNotAllowed);
 }
@@ -0,0 +1,59 @@
+mod common;
+
+use papyri_lang::errors::RuntimeError;
+
+const SVG: &str = r##"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 10 10"><linearGradient id="g"/><use xlink:href="#g"/></svg>"##;
+
+assert_ok! {
+    parse_fragment(
+        r#"@html::parse `<span class="x">Foo <b>bar</b></span>`"#,
+        r#"<p><span class="x">Foo <b>bar</b></span></p>"#,
+    );
+    
+    parse_document(
+        "@html::parse `<!DOCTYPE html><html><head><title>Foo</title></head><body><p>Bar</p></body></html>`",
+        "<html><head><title>Foo</title></head><body><p>Bar</p></body></html>",
+    );
+    
+    parse_match(
+        "@match @html::parse `<span>Foo</span>` {<span>$x</span> -> $x}",
+        "<p>Foo</p>",
+    );
+    
+    parse_xml_case_sensitive(
+        "@html::parse(xml=True) `<Foo Bar=\"baz\"><Qux/></Foo>`",
+        r#"<p><Foo Bar="baz"><Qux></Qux></Foo></p>"#,
+    );
+}
+
+assert_err! {
+    parse_xml_malformed("@html::parse(xml=True) `<foo><bar></foo>`", RuntimeError::XmlParseError);
+}
+
+#[test]
+fn read_html() -> common::TestResult {
+    let files = [("fragment.html", "<p>Foo <em>bar</em></p>")];
+    assert_eq!("<p>Foo <em>bar</em></p>", common::compile_with_files(&files, "@file::read_html `fragment.html`")?);
+    Ok(())
+}
+
+#[test]
+fn read_svg_as_xml() -> common::TestResult {
+    let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10" xmlns:xlink="http://www.w3.org/1999/xlink"><linearGradient id="g"></linearGradient><use xlink:href="#g"></use></svg>"##;
+    assert_eq!(format!("<p>{expected}</p>"), common::compile_with_files(&[("icon.svg", SVG)], "@file::read_html `icon.svg`")?);
+    Ok(())
+}
+
+#[test]
+fn read_html_as_xml() -> common::TestResult {
+    let files = [("page.html", "<Foo><Bar/></Foo>")];
+    assert_eq!("<p><Foo><Bar></Bar></Foo></p>", common::compile_with_files(&files, "@file::read_html(xml=True) `page.html`")?);
+    Ok(())
+}
+
+#[test]
+fn read_html_missing() {
+    let result = common::compile_with_files(&[], "@file::read_html `missing.html`");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::FileReadError(..)));
+}
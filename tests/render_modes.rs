@@ -0,0 +1,90 @@
+mod common;
+
+use std::path::Path;
+use std::rc::Rc;
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{self, ReportingLevel, RuntimeError};
+use papyri_lang::utils::filesystem::MemoryFileSystem;
+
+/// Compiles the source with an output directory, writes the output files
+/// with the default HTML mode, and returns the contents of `/out/page`.
+fn write_page(src: &str) -> Result<String, errors::Diagnostics> {
+    let fs = Rc::new(MemoryFileSystem::new());
+    let mut ctx = Context::new(ReportingLevel::Warning, Some(Path::new("/out")));
+    ctx.file_system = fs.clone();
+    ctx.compile_str(src)?;
+    
+    let out_files: Vec<_> = ctx.out_files.as_mut().unwrap().take_iter().collect();
+    for (path, file) in out_files {
+        ctx.write_out_file(&path, &file, true).unwrap();
+    }
+    let contents = fs.get_file("/out/page").expect("No output file");
+    Ok(String::from_utf8(contents).unwrap())
+}
+
+#[test]
+fn mode_default() -> common::TestResult {
+    assert_eq!("<b>Hello</b> &amp; goodbye", write_page("@file::write(`page`) {@b Hello & goodbye}")?);
+    Ok(())
+}
+
+#[test]
+fn mode_html() -> common::TestResult {
+    assert_eq!("<b>Hello</b> &amp; goodbye", write_page("@file::write(`page`, mode=`html`) {@b Hello & goodbye}")?);
+    Ok(())
+}
+
+#[test]
+fn mode_text() -> common::TestResult {
+    assert_eq!("Hello & goodbye", write_page("@file::write(`page`, mode=`text`) {@b Hello & goodbye}")?);
+    Ok(())
+}
+
+#[test]
+fn mode_raw() -> common::TestResult {
+    assert_eq!("<b>Hello</b> & goodbye", write_page("@file::write(`page`, mode=`raw`) {@b Hello & goodbye}")?);
+    Ok(())
+}
+
+#[test]
+fn mode_markdown() -> common::TestResult {
+    let src = "@file::write(`page`, mode=`markdown`) {
+@h1 Title
+
+Some @b bold, @i italic and @code `a*b` text, with @href(`https://example.com`) {a link}.
+
+<ul><li>one</li><li>two</li></ul>
+
+<ol><li>first</li><li>second</li></ol>
+}";
+    let expected = "# Title
+
+Some **bold**, *italic* and `a*b` text, with [a link](https://example.com).
+
+- one
+- two
+
+1. first
+2. second
+";
+    assert_eq!(expected, write_page(src)?);
+    Ok(())
+}
+
+#[test]
+fn mode_markdown_escape() -> common::TestResult {
+    assert_eq!("2 \\* 3 \\_ 4\n", write_page("@file::write(`page`, mode=`markdown`) {2 * 3 _ 4}")?);
+    Ok(())
+}
+
+#[test]
+fn write_json_raw() -> common::TestResult {
+    assert_eq!("{\"a\":\"<b>\"}", write_page("@file::write_json(`page`) @dict::new(a=`<b>`).")?);
+    Ok(())
+}
+
+#[test]
+fn invalid_mode() {
+    let result = write_page("@file::write(`page`, mode=`pdf`) {Hello}");
+    common::assert_runtime_error(result, |e| matches!(e, RuntimeError::InvalidRenderMode(..)));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+assert_ok! {
+    keep_safe_content(
+        r#"@html::sanitize {<span class="x" title="y">Foo <b>bar</b></span>}"#,
+        r#"<p><span class="x" title="y">Foo <b>bar</b></span></p>"#,
+    );
+    
+    remove_script(
+        "@html::sanitize {Foo<script>alert(1)</script> bar}",
+        "<p>Foo</p><p>bar</p>",
+    );
+    
+    remove_style(
+        "@html::sanitize <div><style>p {color: red}</style>Foo</div>",
+        "<div><p>Foo</p></div>",
+    );
+    
+    remove_event_handler(
+        r#"@html::sanitize <span onclick="alert(1)" onmouseover="alert(2)">Foo</span>"#,
+        "<p><span>Foo</span></p>",
+    );
+    
+    remove_javascript_url(
+        r#"@html::sanitize <a href="javascript:alert(1)">Foo</a>"#,
+        "<p><a>Foo</a></p>",
+    );
+    
+    remove_obfuscated_javascript_url(
+        r#"@html::sanitize <a href=" JavaScript:alert(1)">Foo</a>"#,
+        "<p><a>Foo</a></p>",
+    );
+    
+    keep_safe_urls(
+        r#"@html::sanitize {<a href="https://example.com/">Foo</a> <a href="/foo:bar">Bar</a> <a href="mailto:foo@example.com">Baz</a>}"#,
+        r#"<p><a href="https://example.com/">Foo</a> <a href="/foo:bar">Bar</a> <a href="mailto:foo@example.com">Baz</a></p>"#,
+    );
+    
+    remove_disallowed_attribute(
+        r#"@html::sanitize <span style="display: none" href="/">Foo</span>"#,
+        "<p><span>Foo</span></p>",
+    );
+    
+    unwrap_unknown_tag(
+        "@html::sanitize <font>Foo <i>bar</i></font>",
+        "<p>Foo <i>bar</i></p>",
+    );
+    
+    remove_iframe(
+        r#"@html::sanitize {Foo <iframe src="https://example.com/"></iframe>}"#,
+        "<p>Foo </p>",
+    );
+}
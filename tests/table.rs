@@ -0,0 +1,105 @@
+mod common;
+
+assert_ok! {
+    list_of_lists(
+        "@table [[\"Name\", \"Age\"], [\"Alice\", 30], [\"Bob\", 4]]",
+        "<table><thead><tr><th>Name</th><th>Age</th></tr></thead><tbody><tr><td>Alice</td><td>30</td></tr><tr><td>Bob</td><td>4</td></tr></tbody></table>",
+    );
+    
+    no_header_rows(
+        "@table(header_rows=0) [[1, 2], [3, 4]]",
+        "<table><tbody><tr><td>1</td><td>2</td></tr><tr><td>3</td><td>4</td></tr></tbody></table>",
+    );
+    
+    two_header_rows(
+        "@table(header_rows=2) [[\"A\"], [\"B\"], [\"C\"]]",
+        "<table><thead><tr><th>A</th></tr><tr><th>B</th></tr></thead><tbody><tr><td>C</td></tr></tbody></table>",
+    );
+    
+    list_of_dicts(
+        "@table [@dict::new(name=\"Alice\", age=30)., @dict::new(name=\"Bob\", city=\"Paris\").]",
+        "<table><thead><tr><th>name</th><th>age</th><th>city</th></tr></thead><tbody><tr><td>Alice</td><td>30</td><td></td></tr><tr><td>Bob</td><td></td><td>Paris</td></tr></tbody></table>",
+    );
+    
+    list_of_dicts_no_keys(
+        "@table(header_rows=0) [@dict::new(name=\"Alice\").]",
+        "<table><tbody><tr><td>Alice</td></tr></tbody></table>",
+    );
+    
+    csv(
+        "@table ```\nName,Score\nAlice,\"3,5\"\n```",
+        "<table><thead><tr><th>Name</th><th>Score</th></tr></thead><tbody><tr><td>Alice</td><td>3,5</td></tr></tbody></table>",
+    );
+    
+    caption(
+        "@table(caption=\"People\") [[\"Name\"]]",
+        "<table><caption>People</caption><thead><tr><th>Name</th></tr></thead></table>",
+    );
+    
+    align(
+        "@table(align=\"lr\") [[\"A\", \"B\", \"C\"]]",
+        "<table><thead><tr><th style=\"text-align: left\">A</th><th style=\"text-align: right\">B</th><th>C</th></tr></thead></table>",
+    );
+    
+    decimals(
+        "@table(decimals=2) ```\nName,Score\nAlice,3.14159\nBob,n/a\nCarol,7\n```",
+        "<table><thead><tr><th>Name</th><th>Score</th></tr></thead><tbody><tr><td>Alice</td><td>3.14</td></tr><tr><td>Bob</td><td>n/a</td></tr><tr><td>Carol</td><td>7.00</td></tr></tbody></table>",
+    );
+    
+    decimals_int(
+        "@table(header_rows=0, decimals=1) [[-3]]",
+        "<table><tbody><tr><td>-3.0</td></tr></tbody></table>",
+    );
+    
+    decimals_large_int(
+        "@table(header_rows=0, decimals=2) [[9007199254740993]]",
+        "<table><tbody><tr><td>9007199254740993.00</td></tr></tbody></table>",
+    );
+    
+    decimals_zero(
+        "@table(header_rows=0, decimals=0) [[12, \"3.7\"]]",
+        "<table><tbody><tr><td>12</td><td>4</td></tr></tbody></table>",
+    );
+    
+    html_cells(
+        "@table(header_rows=0) [[<b>x</b>]]",
+        "<table><tbody><tr><td><b>x</b></td></tr></tbody></table>",
+    );
+    
+    label_table(
+        "@label(`t`) @table(caption=\"Results\") [[\"A\"]]\n\n@ref t",
+        "<table id=\"t\"><caption>Table 1: Results</caption><thead><tr><th>A</th></tr></thead></table><p><a href=\"#t\">Table 1</a></p>",
+    );
+}
+
+assert_err! {
+    invalid_align(
+        "@table(align=\"x\") [[1]]",
+        RuntimeError::InvalidTableAlignment,
+    );
+    
+    negative_header_rows(
+        "@table(header_rows=-1) [[1]]",
+        RuntimeError::ParamMustBePositive,
+    );
+    
+    decimals_too_large(
+        "@table(decimals=1000000000) [[1]]",
+        RuntimeError::ParamTooLarge,
+    );
+    
+    not_a_list(
+        "@table 5",
+        TypeError::ExpectedWas,
+    );
+    
+    row_not_a_list(
+        "@table [[1], 2]",
+        TypeError::ExpectedWas,
+    );
+    
+    mixed_dicts_and_lists(
+        "@table [@dict::new(a=1)., [2]]",
+        TypeError::ExpectedWas,
+    );
+}
@@ -0,0 +1,43 @@
+mod common;
+
+assert_ok! {
+    toc_before_headings(
+        "@toc.\n\n@h2 Intro\n\n@h2 Usage",
+        "<ul><li><a href=\"#intro\">Intro</a></li><li><a href=\"#usage\">Usage</a></li></ul><h2 id=\"intro\">Intro</h2><h2 id=\"usage\">Usage</h2>",
+    );
+    
+    toc_nested(
+        "@h1 Guide\n\n@h2 Intro\n\n@h3 Setup\n\n@h2 Usage\n\n@toc.",
+        "<h1 id=\"guide\">Guide</h1><h2 id=\"intro\">Intro</h2><h3 id=\"setup\">Setup</h3><h2 id=\"usage\">Usage</h2><ul><li><a href=\"#guide\">Guide</a><ul><li><a href=\"#intro\">Intro</a><ul><li><a href=\"#setup\">Setup</a></li></ul></li><li><a href=\"#usage\">Usage</a></li></ul></li></ul>",
+    );
+    
+    toc_explicit_id(
+        "@toc.\n\n<h2 id=\"start\">Intro</h2>",
+        "<ul><li><a href=\"#start\">Intro</a></li></ul><h2 id=\"start\">Intro</h2>",
+    );
+    
+    toc_explicit_id_reserved(
+        "@toc.\n\n@h2 Intro\n\n<h2 id=\"intro\">Other</h2>",
+        "<ul><li><a href=\"#intro_2\">Intro</a></li><li><a href=\"#intro\">Other</a></li></ul><h2 id=\"intro_2\">Intro</h2><h2 id=\"intro\">Other</h2>",
+    );
+    
+    toc_duplicate_headings(
+        "@toc.\n\n<h2>Getting started</h2>\n\n<h2>Getting started</h2>",
+        "<ul><li><a href=\"#getting_started\">Getting started</a></li><li><a href=\"#getting_started_2\">Getting started</a></li></ul><h2 id=\"getting_started\">Getting started</h2><h2 id=\"getting_started_2\">Getting started</h2>",
+    );
+    
+    toc_max_level(
+        "@toc(max_level=2).\n\n@h2 Intro\n\n@h3 Setup",
+        "<ul><li><a href=\"#intro\">Intro</a></li></ul><h2 id=\"intro\">Intro</h2><h3 id=\"setup\">Setup</h3>",
+    );
+    
+    toc_no_headings(
+        "@toc.",
+        "",
+    );
+    
+    no_toc_headings_unchanged(
+        "@h2 Intro",
+        "<h2>Intro</h2>",
+    );
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use papyri_lang::compiler::Context;
+use papyri_lang::errors::{self, PapyriError, ReportingLevel, Warning};
+
+/// Compiles the source and validates the output, returning the diagnostics.
+fn validate(src: &str) -> errors::Diagnostics {
+    let mut ctx = Context::new(ReportingLevel::Warning, None);
+    let doc = ctx.compile_document(src);
+    assert!(doc.diagnostics.is_empty(), "{:?}", doc.diagnostics);
+    ctx.validate_html(&doc.html);
+    ctx.diagnostics.take()
+}
+
+#[test]
+fn valid() {
+    let src = "<ul><li>One</li><li>Two</li></ul>
+
+<dl><dt>Term</dt><dd>Definition</dd></dl>
+
+<table><tr><th scope=\"col\">A</th></tr><tr><td colspan=\"2\">1</td></tr></table>
+
+@href(`#a`) {@b link} <span id=\"a\" class=\"x\" data_value=\"1\" aria_label=\"y\">Span</span> <img src=\"a.png\" alt=\"A\">";
+    assert_eq!(Vec::<String>::new(), common::warnings(&validate(src)));
+}
+
+#[test]
+fn li_outside_list() {
+    let d = validate("<div><li>Item</li></div>");
+    assert!(d.has_any(|e| matches!(e, PapyriError::Warning(Warning::TagRequiresParent(name, _)) if name.as_ref() == "li")), "{d:?}");
+}
+
+#[test]
+fn td_outside_table() {
+    let d = validate("<td>Cell</td>");
+    assert!(d.has_any(|e| matches!(e, PapyriError::Warning(Warning::TagRequiresParent(name, _)) if name.as_ref() == "td")), "{d:?}");
+}
+
+#[test]
+fn block_inside_p() {
+    let d = validate("<section>@html::parse(xml=True) `<p>Text <div>Block</div></p>`</section>");
+    assert!(d.has_any(|e| matches!(e, PapyriError::Warning(Warning::TagNotAllowedIn(name, parent)) if name.as_ref() == "div" && parent.as_ref() == "p")), "{d:?}");
+}
+
+#[test]
+fn nested_links() {
+    let d = validate("<a href=\"a\">Outer <a href=\"b\">inner</a></a>");
+    assert!(d.has_any(|e| matches!(e, PapyriError::Warning(Warning::TagNotAllowedIn(name, parent)) if name.as_ref() == "a" && parent.as_ref() == "a")), "{d:?}");
+}
+
+#[test]
+fn duplicate_id() {
+    let d = validate("<h2 id=\"intro\">One</h2>\n\n<h2 id=\"intro\">Two</h2>");
+    assert!(d.has_any(|e| matches!(e, PapyriError::Warning(Warning::DuplicateID(id)) if id.as_ref() == "intro")), "{d:?}");
+    
+    let w = d.iter().next().unwrap();
+    assert_eq!(3, w.range().line_col().0);
+}
+
+#[test]
+fn unknown_attribute() {
+    let d = validate("<img src=\"a.png\" alt=\"A\" hieght=\"10\">");
+    assert!(d.has_any(|e| matches!(e, PapyriError::Warning(Warning::UnknownAttribute(attr, name)) if attr.as_ref() == "hieght" && name.as_ref() == "img")), "{d:?}");
+}
+
+#[test]
+fn custom_elements_not_checked() {
+    assert_eq!(Vec::<String>::new(), common::warnings(&validate("<widget foo=\"bar\">Hello</widget>")));
+}
+
+#[test]
+fn warning_from_stdlib_function() {
+    // `@href` creates the tag in the standard library, so the warning should
+    // point to the call site
+    let d = validate("Some text.\n\n@href(`a`) {@href(`b`) nested}");
+    let w = d.iter().next().expect("No warnings");
+    assert_eq!("<string>", w.range().path_str());
+    assert_eq!(3, w.range().line_col().0);
+}